@@ -0,0 +1,62 @@
+use std::cmp::Ordering;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use aoc_2023_rs::day7::camel_cards::{Deck, Hand, RuleSet};
+
+const HANDS: usize = 1_000_000;
+
+/// A million random hands, always the same ones.
+fn generate_hands(rules: RuleSet) -> Vec<Hand> {
+    let deck = Deck::default();
+    // a plain LCG is random enough here
+    let mut state = 0x2023u64;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 33) as usize
+    };
+
+    (0..HANDS)
+        .map(|_| {
+            let cards: String = (0..5).map(|_| deck.cards.as_bytes()[next() % deck.cards.len()] as char).collect();
+            let bid = next() % 1000;
+
+            Hand::parse_with(&format!("{} {}", cards, bid), &deck, rules).unwrap()
+        })
+        .collect()
+}
+
+/// Compares hands the way they were compared before kinds were cached.
+fn cmp_recomputing_kind(a: &Hand, b: &Hand) -> Ordering {
+    let rules = a.rules();
+
+    rules.kind(a.cards()).cmp(&rules.kind(b.cards()))
+        .then_with(|| {
+            a.cards().iter().zip(b.cards())
+                .map(|(a, b)| rules.cmp_cards(*a, *b))
+                .find(|ord| ord.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
+fn sort_hands(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort 1M hands");
+    group.sample_size(10);
+
+    for (name, rules) in [("standard", RuleSet::STANDARD), ("jokers", RuleSet::JOKERS_WILD)] {
+        let hands = generate_hands(rules);
+
+        group.bench_function(format!("{} cached kind", name), |b| {
+            b.iter_batched_ref(|| hands.clone(), |hands| hands.sort(), BatchSize::LargeInput)
+        });
+
+        group.bench_function(format!("{} recomputed kind", name), |b| {
+            b.iter_batched_ref(|| hands.clone(), |hands| hands.sort_by(cmp_recomputing_kind), BatchSize::LargeInput)
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sort_hands);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+use clap::{Parser, Subcommand};
+
+use aoc_2023_rs::{input::InputArgs, solver, verify::{verify_day, Status}, DAYS};
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the solver for a day against an input file.
+    Run {
+        /// The day of the puzzle to solve.
+        day: u8,
+
+        /// Only runs the given part. Runs every part of the day if missing.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        #[command(flatten)]
+        input: InputArgs
+    },
+
+    /// Checks every solver against the known answers of every input.
+    Verify {
+        /// Only verifies the given day.
+        day: Option<u8>
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run { day, part, input } => {
+            let Some(solver) = solver(day) else {
+                eprintln!("No solver registered for day {}", day);
+                std::process::exit(1);
+            };
+
+            let input = input.read(day);
+
+            let parts = part.map_or(vec![1, 2], |p| vec![p]);
+            let answers = solver.run_all(&parts, &input).unwrap_or_else(|e| e.exit(&input));
+
+            for (part, answer) in parts.iter().zip(answers) {
+                println!("Day {} part {}: {}", day, part, answer.unwrap());
+            }
+        }
+
+        Command::Verify { day } => {
+            if let Some(day) = day.filter(|day| solver(*day).is_none()) {
+                eprintln!("No solver registered for day {}", day);
+                std::process::exit(1);
+            }
+
+            // failures are reported in the table below instead
+            std::panic::set_hook(Box::new(|_| {}));
+
+            let days = day.map_or(DAYS.to_vec(), |d| vec![d]);
+            let outcomes: Vec<_> = days.into_iter().flat_map(verify_day).collect();
+
+            for outcome in &outcomes {
+                let detail = match &outcome.status {
+                    Status::Pass => String::new(),
+                    Status::Mismatch { expected, actual } => format!(" expected {}, got {}", expected, actual),
+                    Status::Fail(message) => format!(" {}", message),
+                    Status::Unverified(actual) => format!(" got {}, but there is no expected answer", actual)
+                };
+
+                println!("{:<10} day {} part {} {:<20} ({:.2?}){}",
+                         outcome.status, outcome.day, outcome.part, outcome.input, outcome.elapsed, detail);
+            }
+
+            let passed = outcomes.iter().filter(|o| o.status == Status::Pass).count();
+            let unverified = outcomes.iter().filter(|o| matches!(o.status, Status::Unverified(_))).count();
+            println!("{}/{} passed, {} unverified", passed, outcomes.len(), unverified);
+
+            if passed + unverified != outcomes.len() {
+                std::process::exit(1);
+            }
+        }
+    }
+}
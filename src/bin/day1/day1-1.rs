@@ -1,20 +1,15 @@
-use std::str::FromStr;
+use clap::Parser;
 
-const INPUT: &str = include_str!("day1-jc.txt");
+use aoc_2023_rs::{day1::{Calibration, Day1}, input::InputArgs};
 
 fn main() {
-    let lines = INPUT.lines();
+    let args = InputArgs::parse();
+    let sum = args.stream_with(1, |line| {
+        let calibration: Calibration = line.parse()?;
+        println!("{} => {}", line, calibration.digits_value()?);
 
-    let sum: u32 = lines.map(|line| {
-        let first_char = line.chars().find(|c| c.is_ascii_digit());
-        let last_char = line.chars().rev().find(|c| c.is_ascii_digit());
-        let string = String::from_iter([first_char.unwrap(), last_char.unwrap()]);
-        let number = u32::from_str(&string[..]).unwrap();
-
-        println!("{} => {:?}, {:?} => {}", line, first_char, last_char, number);
-
-        number
-    }).sum();
+        Ok(calibration)
+    }, |calibrations| Day1.part1_streaming(calibrations));
 
     println!("Sum: {}", sum);
 }
@@ -1,226 +1,140 @@
-use std::{env, cmp::{max}};
+use std::path::PathBuf;
 
-const MAX_R: u32 = 12;
-const MAX_G: u32 = 13;
-const MAX_B: u32 = 14;
+use clap::{Parser, Subcommand, ValueEnum};
 
-#[derive(Debug, Default, Eq, PartialEq)]
-struct GameSet {
-    r: u32,
-    g: u32,
-    b: u32
-}
+use aoc_2023_rs::{day2::{Bag, Conundrum, Day2}, input::InputArgs, ParseError, Solution};
 
-impl GameSet {
-    fn possible(&self) -> bool {
-        self.r <= MAX_R && self.g <= MAX_G && self.b <= MAX_B
-    }
-    
-    fn mult(self) -> u32 {
-        self.r * self.g * self.b
-    }
-}
-
-impl From<&str> for GameSet {
-    // 3 blue, 4 red
-    // 1 red, 2 green, 6 blue
-    // 2 green
-    fn from(value: &str) -> Self {
-        let mut game_set = GameSet::default();
-        let count_colors = value.split(", ");
-        
-        for count_color_string in count_colors {
-            let count_color: Vec<_> = count_color_string.split(" ").collect();
-            
-            let &[count_str, color] = &count_color[..] else { unimplemented!() };
-            let count = u32::from_str_radix(count_str, 10)
-                .expect("Invalid number!");
-            
-            match color {
-                "red" => game_set.r += count,
-                "green" => game_set.g += count,
-                "blue" => game_set.b += count,
-                other => unimplemented!("No color {}", other)
-            }
-        }
-        
-        game_set
-    }
-}
+#[derive(clap::Parser)]
+struct Cli {
+    /// The cubes in the bag, e.g. "20 red, 5 yellow". Defaults to 12 red,
+    /// 13 green and 14 blue cubes.
+    #[arg(long, global = true, value_parser = |s: &str| s.parse::<Bag>().map_err(|e| e.to_string()))]
+    bag: Option<Bag>,
 
-#[derive(Debug, Eq, PartialEq)]
-struct Game {
-    id: u32,
-    game_sets: Vec<GameSet>
-}
+    /// Reads the cubes in the bag from a TOML file with one `<color> = <count>`
+    /// per line.
+    #[arg(long, global = true, conflicts_with = "bag")]
+    bag_file: Option<PathBuf>,
 
-impl Game {
-    fn possible(&self) -> bool {
-        self.game_sets.iter()
-            .map(|game_set: &GameSet| game_set.possible())
-            .reduce(|acc, b| acc && b)
-            .unwrap_or_default() /* false */
-    }
-    
-    fn minimum_game_set(self) -> GameSet {
-        self.game_sets.into_iter()
-            .reduce(|acc, b| GameSet {
-                r: max(acc.r, b.r),
-                g: max(acc.g, b.g),
-                b: max(acc.b, b.b)
-            }).unwrap()
-    }
-}
+    /// Reads the games one at a time instead of all at once, for inputs too
+    /// large to fit in memory. Reports still read every game first.
+    #[arg(long)]
+    stream: bool,
 
-impl From<&str> for Game {
-    // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green    
-    fn from(value: &str) -> Self {
-        let parts: Vec<_> = value.split(": ").collect();
-        
-        let &[game_and_number, game_sets_str] = &parts[..] else { unimplemented!() };
-        
-        let game_sets: Vec<_> = game_sets_str.split("; ")
-            .map(|x| GameSet::from(x))
-            .collect();
-        
-        let id = u32::from_str_radix(&game_and_number.replace("Game ", "")[..], 10)
-            .expect("Invalid number!");
-        
-        Self { id, game_sets }
-    }
-}
+    #[command(subcommand)]
+    command: Option<Command>,
 
-#[derive(Debug)]
-struct Conundrum {
-    games: Vec<Game>
+    #[command(flatten)]
+    input: InputArgs
 }
 
-impl Conundrum {
-    fn sum_of_possible_games(&self) -> u32 {
-        self.games.iter()
-            .filter(|game| game.possible())
-            .map(|game| game.id)
-            .sum()
+#[derive(Subcommand)]
+enum Command {
+    /// Lists the minimum bag, the power and the first impossible draw of every
+    /// game, followed by how many cubes of each color the games needed.
+    Report {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+
+        // The input may come after `report` as well as before it.
+        #[command(flatten)]
+        input: InputArgs
     }
 }
 
-impl From<Vec<Game>> for Conundrum {
-    fn from(value: Vec<Game>) -> Self {
-        Self { games: value }
-    }
+#[derive(ValueEnum, Clone, Copy)]
+enum Format {
+    Table,
+    Csv
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn report(day2: &Day2, conundrum: &Conundrum, format: Format) -> Result<(), ParseError> {
+    let colors = conundrum.colors(&day2.bag);
+    let stats = conundrum.color_stats(&day2.bag);
     
-    #[test]
-    fn test_game_set_possible() {
-        assert_eq!(true, GameSet {
-            r: 1,
-            g: 2,
-            b: 3
-        }.possible());
-        
-        assert_eq!(false, GameSet {
-            r: 20,
-            g: 2,
-            b: 3
-        }.possible());
-    }
-
-    #[test]
-    fn test_game_set_parse() {
-        const SAMPLE: &str = "1 red, 2 green, 6 blue";
-        
-        let game_set = GameSet::from(SAMPLE);
+    match format {
+        Format::Table => {
+            print!("{:>6}", "game");
+            colors.iter().for_each(|color| print!(" {:>8}", color));
+            println!(" {:>8}  first impossible draw", "power");
+            
+            for game in &conundrum.games {
+                let minimum = game.minimum_game_set();
+                let impossible = game.first_impossible_draw(&day2.bag)
+                    .map_or(String::from("-"), |(i, draw)| format!("#{}: {}", i + 1, draw));
+                
+                print!("{:>6}", game.id);
+                colors.iter().for_each(|color| print!(" {:>8}", minimum.count(color)));
+                println!(" {:>8}  {}", minimum.power(&day2.bag)?, impossible);
+            }
+            
+            println!();
+            println!("{:<8} {:>8} {:>8}", "color", "max", "mean");
+            
+            for (color, stats) in &stats {
+                println!("{:<8} {:>8} {:>8.2}", color, stats.max, stats.mean);
+            }
+        }
         
-        assert_eq!(GameSet { r: 1, g: 2, b: 6 }, game_set);
-    }
-
-    #[test]
-    fn test_game_parse() {
-        assert_eq!(Game {
-            id: 1,
-            game_sets: vec![
-                GameSet { r: 4, g: 0, b: 3 },
-                GameSet { r: 1, g: 2, b: 6 },
-                GameSet { r: 0, g: 2, b: 0 }
-            ]
-        }, Game::from("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"))
-    }
-
-    #[test]
-    fn test_game_possible() {
-        let game = Game {
-            id: 42,
-            game_sets: vec![
-                GameSet {
-                    r: 2,
-                    g: 3,
-                    b: 4
-                }
-            ]
-        };
-
-        assert_eq!(true, game.possible());
-    }
-
-    #[test]
-    fn test_game_possible_with_max_values() {
-        let game = Game {
-            id: 42,
-            game_sets: vec![
-                GameSet {
-                    r: MAX_R,
-                    g: MAX_G,
-                    b: MAX_B
-                }
-            ]
-        };
-
-        assert_eq!(true, game.possible());
+        // two tables separated by an empty line: the games, then the colors
+        Format::Csv => {
+            println!("game,{},power,first_impossible_draw", colors.join(","));
+            
+            for game in &conundrum.games {
+                let minimum = game.minimum_game_set();
+                let counts: Vec<_> = colors.iter().map(|color| minimum.count(color).to_string()).collect();
+                let impossible = game.first_impossible_draw(&day2.bag)
+                    .map_or(String::new(), |(i, _)| (i + 1).to_string());
+                
+                println!("{},{},{},{}", game.id, counts.join(","), minimum.power(&day2.bag)?, impossible);
+            }
+            
+            println!();
+            println!("color,max,mean");
+            
+            for (color, stats) in &stats {
+                println!("{},{},{}", color, stats.max, stats.mean);
+            }
+        }
     }
     
-    #[test]
-    fn test_game_impossible() {
-        let game = Game {
-            id: 42,
-            game_sets: vec![
-                GameSet {
-                    r: 42,
-                    g: 53,
-                    b: 64
-                }
-            ]
-        };
-        
-        assert_eq!(false, game.possible());
-    }
+    Ok(())
 }
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file_name = &args[1];
+    let cli = Cli::parse();
     
-    let bytes = std::fs::read(file_name)
-        .expect("Failed to read file!");
+    let bag = match (cli.bag, cli.bag_file) {
+        (Some(bag), _) => bag,
+        (None, Some(path)) => Bag::load(&path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        (None, None) => Bag::default()
+    };
+    let day2 = Day2 { bag };
     
-    let string = String::from_utf8(bytes).expect("Invalid file contents! Not UTF-8?");
+    if cli.stream && cli.command.is_none() {
+        let (ids, powers) = cli.input.stream(2, |games| day2.solve_streaming(games));
+        
+        println!("Sum of all possible games => {}", ids);
+        println!("{:#?}", powers);
+        return;
+    }
     
-    let conundrum: Conundrum = string
-        .lines()
-        .filter(|v| !v.is_empty())
-        .map(|line| Game::from(line))
-        .collect::<Vec<Game>>()
-        .into();
+    let (input, format) = match cli.command {
+        Some(Command::Report { format, input }) if input.file_path.is_some() || input.who.is_some() => (input, Some(format)),
+        Some(Command::Report { format, .. }) => (cli.input, Some(format)),
+        None => (cli.input, None)
+    };
     
-//    println!("{:#?}", conundrum);
-    println!("Sum of all possible games => {}", conundrum.sum_of_possible_games());
+    let string = input.read(2);
+    let conundrum = day2.parse(&string).unwrap_or_else(|e| e.exit(&string));
     
-    let minimums: u32 = conundrum.games.into_iter()
-        .map(|game| game.minimum_game_set().mult())
-        .sum();
+    if let Some(format) = format {
+        return report(&day2, &conundrum, format).unwrap_or_else(|e| e.exit(&string));
+    }
     
-    println!("{:#?}", minimums);
+    println!("Sum of all possible games => {}", day2.part1(&conundrum).unwrap_or_else(|e| e.exit(&string)));
+    println!("{:#?}", day2.part2(&conundrum).unwrap_or_else(|e| e.exit(&string)));
 }
@@ -1,76 +1,43 @@
-use std::{env, collections::{HashSet}};
+use clap::Parser;
 
-#[derive(Debug, Clone)]
-struct Card {
-    id: u32,
-    copies: u32,
-    winning_numbers: HashSet<u32>,
-    my_numbers: HashSet<u32>
-}
+use aoc_2023_rs::{day4::{Card, Day4, Scoring}, input::InputArgs, Solution};
 
-impl Card {
-    fn my_winning_numbers(&self) -> impl Iterator<Item = &u32> {
-        self.winning_numbers.intersection(&self.my_numbers)
-    }
-    
-    fn score(&self) -> u32 {
-        let my_winning_numbers: Vec<_> = self.my_winning_numbers().collect();
-        if my_winning_numbers.len() == 0 {
-            return 0
-        }
-        
-        2u32.pow((my_winning_numbers.len() - 1usize) as u32)
-    }
-    
-    fn winning_number_count(&self) -> u32 {
-        self.my_winning_numbers().count() as u32
-    }
-}
+#[derive(clap::Parser)]
+struct Cli {
+    /// How numbers that are on a side of a card more than once count.
+    #[arg(long, value_enum, default_value_t = Scoring::Set)]
+    scoring: Scoring,
 
-impl From<&str> for Card {
-    // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53    
-    fn from(value: &str) -> Self {
-        let id_str = &value[4..8];
-        let Some((winning_numbers_str, my_numbers_str)) = &value[10..].split_once('|') else { unimplemented!("Invalid line {}", value) };
-        
-        let winning_numbers: HashSet<_> = winning_numbers_str.split(' ').filter(|v| !v.is_empty()).map(|v| u32::from_str_radix(v, 10).unwrap()).collect();
-        let my_numbers: HashSet<_> = my_numbers_str.split(' ').filter(|v| !v.is_empty()).map(|v| u32::from_str_radix(v, 10).unwrap()).collect();
-        
-        Self {
-            id: u32::from_str_radix(id_str.trim(), 10).unwrap(),
-            copies: 1,
-            winning_numbers,
-            my_numbers
-        }
-    }
+    /// Reads the cards one at a time instead of all at once, for inputs too
+    /// large to fit in memory. Only prints the sums.
+    #[arg(long)]
+    stream: bool,
+
+    #[command(flatten)]
+    input: InputArgs
 }
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file_name = &args[1];
-
-    let bytes = std::fs::read(file_name)
-        .expect("Failed to read file!");
-
-    let string = String::from_utf8(bytes).expect("Invalid file contents! Not UTF-8?");
-    
-    let mut cards: Vec<_> = string.lines()
-        .map(|v| Card::from(v))
-        .collect();
+    let cli = Cli::parse();
+    let day4 = Day4 { scoring: cli.scoring };
     
-    for i in 0..cards.len() {
-        let card = cards[i].clone();
-        let card_idx = (card.id - 1) as usize;
-        let count = card.winning_number_count() as usize;
+    if cli.stream {
+        let scoring = cli.scoring;
+        let (scores, copies) = cli.input.stream_with(4, move |line| Card::parse_with(line, scoring), |cards| day4.solve_streaming(cards));
         
-        if count == 0 { continue }
-        
-        for j in (card_idx + 1)..(card_idx + 1 + count) {
-            cards[j].copies += card.copies;
-        }
+        println!("Sum of scores: {}", scores);
+        println!("Sum of copies: {}", copies);
+        return;
+    }
+    
+    let string = cli.input.read(4);
+    let table = day4.parse(&string).unwrap_or_else(|e| e.exit(&string));
+    
+    for row in table.rows() {
+        let row = row.unwrap_or_else(|e| e.exit(&string));
+        println!("Card {}: {} wins, {} copies, {} points", row.card.id, row.wins, row.copies, row.points);
     }
     
-    println!("{:?}", cards);
-    println!("Sum of scores: {}", cards.iter().map(|v| v.score()).sum::<u32>());
-    println!("Sum of copies: {}", cards.iter().map(|v| v.copies).sum::<u32>())
+    println!("Sum of scores: {}", table.total_points().unwrap_or_else(|e| e.exit(&string)));
+    println!("Sum of copies: {}", table.total_copies().unwrap_or_else(|e| e.exit(&string)))
 }
@@ -1,19 +1,11 @@
-use std::{env, sync::Arc};
+use clap::Parser;
 
-use crate::common::ConversionMapper;
-
-mod common;
+use aoc_2023_rs::{day5::Day5, input::InputArgs, Solution};
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file_name = &args[1];
-
-    let bytes = std::fs::read(file_name)
-        .expect("Failed to read file!");
-
-    let string = String::from_utf8(bytes).expect("Invalid file contents! Not UTF-8?");
-    let mapper = Arc::new(ConversionMapper::from(&string[..]));
+    let string = InputArgs::parse().read(5);
+    let day5 = Day5::default();
+    let mapper = day5.parse(&string).unwrap_or_else(|e| e.exit(&string));
 
-    let min_seed = mapper.seeds.iter().map(|v| mapper.lookup(*v)).min();
-    println!("Lowest seed: {:?}", min_seed);
+    println!("Lowest seed: {}", day5.part1(&mapper).unwrap_or_else(|e| e.exit(&string)));
 }
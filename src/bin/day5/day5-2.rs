@@ -1,10 +1,8 @@
-use std::{env, sync::Arc, io::{self, Write}, path::PathBuf};
+use std::{io::{self, Write}, ops::Range};
 
 use clap::Parser;
 
-use crate::common::ConversionMapper;
-
-mod common;
+use aoc_2023_rs::{day5::{ConversionMapper, Day5}, input::InputArgs, Solution};
 
 #[derive(clap::Parser)]
 struct Cli {
@@ -14,18 +12,17 @@ struct Cli {
     #[arg(long)]
     no_printing: bool,
 
-    file_path: PathBuf
-}
-
-fn main() {
-    let cli = Cli::parse();
-    let bytes = std::fs::read(cli.file_path)
-        .expect("Failed to read file!");
+    /// Looks up every seed one by one instead of splitting seed ranges.
+    ///
+    /// Very slow, but useful to cross-check the range-based lookup.
+    #[arg(long)]
+    brute_force: bool,
 
-    let string = String::from_utf8(bytes).expect("Invalid file contents! Not UTF-8?");
-    let mapper = Arc::new(ConversionMapper::from(&string[..]));
+    #[command(flatten)]
+    input: InputArgs
+}
 
-    let seed_ranges = mapper.seeds.chunks_exact(2).map(|v| (v[0], v[1]));
+fn brute_force(cli: &Cli, mapper: &ConversionMapper, seed_ranges: Vec<Range<i64>>) -> i64 {
     let mut smallest = i64::MAX;
     
     if !cli.no_printing {
@@ -34,7 +31,9 @@ fn main() {
     
     let mut total_iterations = 0u128;
     
-    for (start, count) in seed_ranges {
+    for Range { start, end } in seed_ranges {
+        let count = end - start;
+        
         if !cli.no_printing {
             println!("\x1b[0G\x1b[2KStarting range {}..{}", start, count);
             print!("Current smallest: {} (iteration: 0/{})", smallest, count);
@@ -49,7 +48,7 @@ fn main() {
                     print!("Current smallest: {}", smallest);
                 }
                 smallest = result;
-            } else if total_iterations % (1 << 18) == 0 && !cli.no_printing {
+            } else if total_iterations.is_multiple_of(1 << 18) && !cli.no_printing {
                 print!("\x1b[0G\x1b[2KCurrent smallest: {} (iteration: {}/{})", smallest, i - start, count);
                 io::stdout().flush().expect("No stdout to flush!");
             } else if !cli.no_printing {
@@ -58,6 +57,23 @@ fn main() {
         }
     }
     
+    smallest
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let string = cli.input.read(5);
+    let day5 = Day5 { brute_force: cli.brute_force };
+    let mapper = day5.parse(&string).unwrap_or_else(|e| e.exit(&string));
+
+    let smallest = if cli.brute_force {
+        let seed_ranges = mapper.seed_ranges().unwrap_or_else(|e| e.exit(&string));
+        
+        brute_force(&cli, &mapper, seed_ranges)
+    } else {
+        day5.part2(&mapper).unwrap_or_else(|e| e.exit(&string))
+    };
+    
     if cli.no_printing {
         println!("{}", smallest);
     } else {
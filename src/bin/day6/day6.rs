@@ -1,73 +1,61 @@
-use std::{env, cmp::Ordering};
+use clap::Parser;
 
-#[derive(Debug, Eq)]
-#[repr(transparent)]
-struct RaceResult {
-    distance: u64
-}
-
-impl PartialOrd<u64> for RaceResult {
-    fn partial_cmp(&self, other: &u64) -> Option<Ordering> {
-        Some(self.distance.cmp(&other))
-    }
-}
+use aoc_2023_rs::{day6::Day6, input::InputArgs, Solution};
 
-impl PartialEq<u64> for RaceResult {
-    fn eq(&self, other: &u64) -> bool {
-        self.distance == *other
-    }
-}
-
-impl PartialEq for RaceResult {
-    fn eq(&self, other: &Self) -> bool {
-        self.distance == other.distance
-    }
-}
+#[derive(clap::Parser)]
+struct Cli {
+    /// Simulates every possible charge time instead of solving for the
+    /// winning ones. Slow, but useful as a reference.
+    #[arg(long)]
+    simulate: bool,
 
-fn simulate_once(charge_time: u64, travel_time: u64) -> RaceResult {
-    let speed = charge_time;
-    let distance = speed * travel_time;
-    
-    RaceResult { distance }
-}
+    /// Reads the input as a single race, ignoring the spaces between the
+    /// numbers.
+    #[arg(long)]
+    kerning: bool,
 
-fn simulate(total_time: u64) -> Vec<RaceResult> {
-    (1..total_time).into_iter()
-        .map(|charge_time| simulate_once(charge_time, total_time - charge_time))
-        .collect()
-}
+    /// Reads the sheet bit by bit instead of all at once, for sheets too large
+    /// to fit in memory. Only prints the product.
+    #[arg(long)]
+    stream: bool,
 
-fn find_winning_records(total_time: u64, record: u64) -> Vec<RaceResult> {
-    simulate(total_time).into_iter()
-        .filter(|v| v > &record)
-        .collect()
+    #[command(flatten)]
+    input: InputArgs
 }
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
-    let file_name = &args[1];
-
-    let bytes = std::fs::read(file_name)
-        .expect("Failed to read file!");
-
-    let string = String::from_utf8(bytes).expect("Invalid file contents! Not UTF-8?");
-    let mut lines = string.lines();
-    let time_line = &lines.next().unwrap()[10..];
-    let distance_line = &lines.next().unwrap()[10..];
+    let cli = Cli::parse();
+    let day6 = Day6 { simulate: cli.simulate, kerning: cli.kerning };
+    
+    if cli.stream {
+        let product = day6.part1_streaming(cli.input.open(6)).unwrap_or_else(|e| {
+            eprintln!("{}", e.report_line(None));
+            std::process::exit(1);
+        });
+        
+        return println!("Product: {}", product);
+    }
     
-    let times = time_line.split(' ')
-        .filter(|v| !v.is_empty())
-        .map(|v| u64::from_str_radix(v, 10).unwrap());
-    let distances = distance_line.split(' ')
-        .filter(|v| !v.is_empty())
-        .map(|v| u64::from_str_radix(v, 10).unwrap());
+    let string = cli.input.read(6);
     
-    let records = times.zip(distances)
-        .map(|(time, dist)| find_winning_records(time, dist));
+    let races = day6.parse(&string).unwrap_or_else(|e| e.exit(&string));
     
-    for record in records.clone() {
-        println!("{:?}", record.len());
+    for race in day6.races(&races).unwrap_or_else(|e| e.exit(&string)) {
+        let ways_to_win = race.ways_to_win();
+        
+        if !cli.simulate {
+            println!("{}", ways_to_win);
+            continue;
+        }
+        
+        let simulated = race.simulated_ways_to_win();
+        
+        if simulated == ways_to_win {
+            println!("{}", simulated);
+        } else {
+            println!("{} (solver disagrees: {})", simulated, ways_to_win);
+        }
     }
     
-    println!("Product: {:?}", records.map(|v| v.len()).product::<usize>())
+    println!("Product: {}", day6.part1(&races).unwrap_or_else(|e| e.exit(&string)))
 }
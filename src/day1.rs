@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use crate::{error::{end_column, parse_lines}, ParseError, Solution};
+
+const DIGITS: &[(&str, u32)] = &[
+    ("zero", 0), ("0", 0),
+    ("one", 1), ("1", 1),
+    ("two", 2), ("2", 2),
+    ("three", 3), ("3", 3),
+    ("four", 4), ("4", 4),
+    ("five", 5), ("5", 5),
+    ("six", 6), ("6", 6),
+    ("seven", 7), ("7", 7),
+    ("eight", 8), ("8", 8),
+    ("nine", 9), ("9", 9),
+];
+
+/// Combines the first and last digit on the line, only counting spelled out
+/// digits if `spelled` is set.
+fn combine_digits(line: &str, spelled: bool) -> Result<u32, ParseError> {
+    let digits = || DIGITS.iter().filter(|(text, _)| spelled || text.len() == 1);
+    let first = digits().filter_map(|(text, value)| line.find(text).map(|i| (i, *value))).min();
+    let last = digits().filter_map(|(text, value)| line.rfind(text).map(|i| (i, *value))).max();
+
+    match (first, last) {
+        (Some((_, first)), Some((_, last))) => Ok(first * 10 + last),
+        _ => Err(ParseError::missing(end_column(line), "a digit"))
+    }
+}
+
+/// Combines the first and last digit on the line.
+pub fn calibration_value(line: &str) -> Result<u32, ParseError> {
+    combine_digits(line, false)
+}
+
+/// Like [`calibration_value`], but digits may also be spelled out.
+pub fn spelled_calibration_value(line: &str) -> Result<u32, ParseError> {
+    combine_digits(line, true)
+}
+
+/// The calibration values of a line. Every line needs at least one digit,
+/// spelled out or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    /// The value from digits alone. Lines with only spelled out digits have
+    /// none, which part 1 reports as an error.
+    pub digits: Option<u32>,
+    /// The value with spelled out digits too.
+    pub spelled: u32,
+    /// Where the line is, to point at if part 1 finds no digits on it. The
+    /// line is 0 until moved there with [`Calibration::at_line`].
+    line: usize,
+    end: usize
+}
+
+impl Calibration {
+    /// Moves the calibration to the given line of the input.
+    pub fn at_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+
+    /// The value from digits alone, failing for lines without any.
+    pub fn digits_value(&self) -> Result<u32, ParseError> {
+        self.digits.ok_or_else(|| ParseError::missing(self.end, "a digit").at_line(self.line))
+    }
+}
+
+impl FromStr for Calibration {
+    type Err = ParseError;
+
+    // two1nine
+    fn from_str(line: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            digits: calibration_value(line).ok(),
+            spelled: spelled_calibration_value(line)?,
+            line: 0,
+            end: end_column(line)
+        })
+    }
+}
+
+pub struct Day1;
+
+/// Adds up `values`, failing if the sum doesn't fit in a u64.
+fn sum(mut values: impl Iterator<Item = Result<u32, ParseError>>) -> Result<u64, ParseError> {
+    values.try_fold(0u64, |sum, value| {
+        sum.checked_add(value?.into())
+            .ok_or_else(|| ParseError::too_large("the sum of calibration values"))
+    })
+}
+
+impl Day1 {
+    /// Sums the calibration values of the lines as they are read.
+    pub fn part1_streaming(&self, calibrations: impl Iterator<Item = Result<Calibration, ParseError>>) -> Result<u64, ParseError> {
+        sum(calibrations.map(|calibration| calibration?.digits_value()))
+    }
+
+    /// Like [`Day1::part1_streaming`], with spelled out digits.
+    pub fn part2_streaming(&self, calibrations: impl Iterator<Item = Result<Calibration, ParseError>>) -> Result<u64, ParseError> {
+        sum(calibrations.map(|calibration| Ok(calibration?.spelled)))
+    }
+}
+
+impl Solution for Day1 {
+    type Input = Vec<Calibration>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        let line_numbers = input.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, _)| i + 1);
+
+        Ok(parse_lines::<Calibration>(input)?.into_iter()
+            .zip(line_numbers)
+            .map(|(calibration, line)| calibration.at_line(line))
+            .collect())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<u64, ParseError> {
+        self.part1_streaming(input.iter().copied().map(Ok))
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<u64, ParseError> {
+        self.part2_streaming(input.iter().copied().map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::records;
+
+    #[test]
+    fn test_calibration_values() {
+        let calibration = |line: &str| line.parse().map(|c: Calibration| (c.digits, c.spelled));
+
+        assert_eq!(Ok((Some(77), 77)), calibration("treb7uchet"));
+        assert_eq!(Ok((Some(11), 21)), calibration("two1"));
+        assert_eq!(Ok((None, 83)), calibration("eightwothree"));
+    }
+
+    #[test]
+    fn test_missing_digit() {
+        assert_eq!(Err(ParseError::missing(4, "a digit").at_line(2)), Day1.parse("a1b\nabc"));
+        assert_eq!(Err(ParseError::missing(4, "a digit").at_line(2)), Day1.part2_streaming(records("a1b\nabc".as_bytes())));
+
+        // part 2 reads the spelled out digit, but part 1 has none to read
+        let calibrations = Day1.parse("a1b\n\ntwo").unwrap();
+
+        assert_eq!(Ok(11 + 22), Day1.part2(&calibrations));
+        assert_eq!(Err(ParseError::missing(4, "a digit").at_line(3)), Day1.part1(&calibrations));
+        // streaming leaves the line to whoever reads the records
+        assert_eq!(Err(ParseError::missing(4, "a digit").at_line(0)), Day1.part1_streaming(records("a1b\n\ntwo".as_bytes())));
+    }
+}
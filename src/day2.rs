@@ -0,0 +1,444 @@
+use std::{collections::BTreeMap, fmt, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::{error::{column_of, end_column, parse_lines, parse_number}, ParseError, Solution};
+
+pub const MAX_R: u32 = 12;
+pub const MAX_G: u32 = 13;
+pub const MAX_B: u32 = 14;
+
+/// The cubes of each color drawn at once, keyed by color name.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct GameSet {
+    pub cubes: BTreeMap<String, u32>
+}
+
+impl GameSet {
+    /// How many cubes of `color` were drawn.
+    pub fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or_default()
+    }
+    
+    pub fn possible(&self, bag: &Bag) -> bool {
+        self.cubes.iter().all(|(color, count)| *count <= bag.limit(color))
+    }
+    
+    /// The product of the cube counts of every color in the bag, failing if
+    /// that doesn't fit in a u64.
+    pub fn power(&self, bag: &Bag) -> Result<u64, ParseError> {
+        bag.colors().try_fold(1u64, |power, color| {
+            power.checked_mul(self.count(color).into())
+                .ok_or_else(|| ParseError::too_large("the power"))
+        })
+    }
+}
+
+impl<const N: usize> From<[(&str, u32); N]> for GameSet {
+    fn from(value: [(&str, u32); N]) -> Self {
+        Self {
+            cubes: value.into_iter()
+                .map(|(color, count)| (color.to_owned(), count))
+                .collect()
+        }
+    }
+}
+
+impl fmt::Display for GameSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (color, count)) in self.cubes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            
+            write!(f, "{} {}", count, color)?;
+        }
+        
+        Ok(())
+    }
+}
+
+impl FromStr for GameSet {
+    type Err = ParseError;
+
+    // 3 blue, 4 red
+    // 1 red, 2 green, 6 blue
+    // 2 green
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        let mut game_set = GameSet::default();
+        let count_colors = value.split(", ");
+        
+        for count_color_string in count_colors {
+            let column = column_of(value, count_color_string);
+            let Some((count_str, color)) = count_color_string.split_once(' ') else {
+                return Err(ParseError::unexpected(column, count_color_string, "\"<count> <color>\""));
+            };
+            
+            let count: u32 = parse_number(value, count_str)?;
+            
+            if color.is_empty() || !color.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(ParseError::unexpected(column_of(value, color), color, "a color name"));
+            }
+            
+            let total = game_set.cubes.entry(color.to_owned()).or_default();
+            *total = total.checked_add(count)
+                .ok_or_else(|| ParseError::too_large(format!("the number of {} cubes", color)).at_line(1).offset(column - 1))?;
+        }
+        
+        Ok(game_set)
+    }
+}
+
+/// How many cubes of each color are in the bag. Colors that aren't in it
+/// can't be drawn at all.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Bag {
+    pub limits: BTreeMap<String, u32>
+}
+
+impl Bag {
+    pub fn limit(&self, color: &str) -> u32 {
+        self.limits.get(color).copied().unwrap_or_default()
+    }
+    
+    pub fn colors(&self) -> impl Iterator<Item = &str> {
+        self.limits.keys().map(String::as_str)
+    }
+    
+    /// Reads a bag from a TOML file like `red = 12`, one color per line.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let string = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        
+        toml::from_str(&string)
+            .map_err(|e| format!("Invalid bag {}: {}", path.display(), e))
+    }
+}
+
+impl Default for Bag {
+    /// The bag of the puzzle: 12 red, 13 green and 14 blue cubes.
+    fn default() -> Self {
+        GameSet::from([("red", MAX_R), ("green", MAX_G), ("blue", MAX_B)]).into()
+    }
+}
+
+impl From<GameSet> for Bag {
+    fn from(value: GameSet) -> Self {
+        Self { limits: value.cubes }
+    }
+}
+
+impl FromStr for Bag {
+    type Err = ParseError;
+
+    // 20 red, 5 yellow
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        Ok(value.parse::<GameSet>()?.into())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Game {
+    pub id: u32,
+    pub game_sets: Vec<GameSet>
+}
+
+impl Game {
+    pub fn possible(&self, bag: &Bag) -> bool {
+        self.game_sets.iter()
+            .map(|game_set: &GameSet| game_set.possible(bag))
+            .reduce(|acc, b| acc && b)
+            .unwrap_or_default() /* false */
+    }
+    
+    pub fn minimum_game_set(&self) -> GameSet {
+        self.game_sets.iter()
+            .fold(GameSet::default(), |mut acc, b| {
+                for (color, count) in &b.cubes {
+                    let minimum = acc.cubes.entry(color.clone()).or_default();
+                    *minimum = (*minimum).max(*count);
+                }
+                
+                acc
+            })
+    }
+    
+    /// The first draw that needed more cubes than are in the bag, along with
+    /// its index.
+    pub fn first_impossible_draw(&self, bag: &Bag) -> Option<(usize, &GameSet)> {
+        self.game_sets.iter()
+            .enumerate()
+            .find(|(_, game_set)| !game_set.possible(bag))
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseError;
+
+    // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green    
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        let Some((game_and_number, game_sets_str)) = value.split_once(": ") else {
+            return Err(ParseError::missing(end_column(value), "\": \" after the game number"));
+        };
+        
+        let Some(id_str) = game_and_number.strip_prefix("Game ") else {
+            return Err(ParseError::unexpected(1, game_and_number, "\"Game <id>\""));
+        };
+        
+        let id = parse_number(value, id_str)?;
+        
+        let game_sets = game_sets_str.split("; ")
+            .map(|x| x.parse::<GameSet>().map_err(|e| e.offset(column_of(value, x) - 1)))
+            .collect::<Result<_, _>>()?;
+        
+        Ok(Self { id, game_sets })
+    }
+}
+
+#[derive(Debug)]
+pub struct Conundrum {
+    pub games: Vec<Game>
+}
+
+impl Conundrum {
+    pub fn sum_of_possible_games(&self, bag: &Bag) -> Result<u64, ParseError> {
+        self.games.iter()
+            .filter(|game| game.possible(bag))
+            .try_fold(0u64, |sum, game| {
+                sum.checked_add(game.id.into())
+                    .ok_or_else(|| ParseError::too_large("the sum of possible games"))
+            })
+    }
+    
+    pub fn sum_of_powers(&self, bag: &Bag) -> Result<u64, ParseError> {
+        self.games.iter()
+            .try_fold(0u64, |sum, game| {
+                sum.checked_add(game.minimum_game_set().power(bag)?)
+                    .ok_or_else(|| ParseError::too_large("the sum of powers"))
+            })
+    }
+}
+
+/// How many cubes of one color the games needed at least.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStats {
+    pub max: u32,
+    pub mean: f64
+}
+
+impl Conundrum {
+    /// The colors in the bag along with any others drawn in the games.
+    pub fn colors<'a>(&'a self, bag: &'a Bag) -> Vec<&'a str> {
+        let mut colors: Vec<_> = bag.colors()
+            .chain(self.games.iter()
+                .flat_map(|game| &game.game_sets)
+                .flat_map(|game_set| game_set.cubes.keys().map(String::as_str)))
+            .collect();
+        
+        colors.sort();
+        colors.dedup();
+        colors
+    }
+    
+    /// The largest and the mean number of cubes of each color needed by a
+    /// single game.
+    pub fn color_stats(&self, bag: &Bag) -> BTreeMap<String, ColorStats> {
+        let minimums: Vec<_> = self.games.iter()
+            .map(|game| game.minimum_game_set())
+            .collect();
+        
+        self.colors(bag).into_iter()
+            .map(|color| {
+                let counts = minimums.iter().map(|minimum| minimum.count(color));
+                let stats = ColorStats {
+                    max: counts.clone().max().unwrap_or_default(),
+                    mean: counts.map(u64::from).sum::<u64>() as f64 / minimums.len().max(1) as f64
+                };
+                
+                (color.to_owned(), stats)
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<Game>> for Conundrum {
+    fn from(value: Vec<Game>) -> Self {
+        Self { games: value }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Day2 {
+    pub bag: Bag
+}
+
+impl Day2 {
+    /// Works out both parts as the games are read, in a single pass.
+    pub fn solve_streaming(&self, games: impl Iterator<Item = Result<Game, ParseError>>) -> Result<(u64, u64), ParseError> {
+        games.into_iter().try_fold((0u64, 0u64), |(ids, powers), game| {
+            let game = game?;
+            let id = if game.possible(&self.bag) { game.id } else { 0 };
+            let ids = ids.checked_add(id.into())
+                .ok_or_else(|| ParseError::too_large("the sum of possible games"))?;
+            let powers = powers.checked_add(game.minimum_game_set().power(&self.bag)?)
+                .ok_or_else(|| ParseError::too_large("the sum of powers"))?;
+            
+            Ok((ids, powers))
+        })
+    }
+}
+
+impl Solution for Day2 {
+    type Input = Conundrum;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Conundrum, ParseError> {
+        Ok(parse_lines::<Game>(input)?.into())
+    }
+
+    fn part1(&self, input: &Conundrum) -> Result<u64, ParseError> {
+        input.sum_of_possible_games(&self.bag)
+    }
+
+    fn part2(&self, input: &Conundrum) -> Result<u64, ParseError> {
+        input.sum_of_powers(&self.bag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_game_set_possible() {
+        assert!(GameSet::from([("red", 1), ("green", 2), ("blue", 3)]).possible(&Bag::default()));
+        assert!(!GameSet::from([("red", 20), ("green", 2), ("blue", 3)]).possible(&Bag::default()));
+        assert!(!GameSet::from([("yellow", 1)]).possible(&Bag::default()));
+    }
+
+    #[test]
+    fn test_game_set_parse() {
+        const SAMPLE: &str = "1 red, 2 green, 6 blue";
+        
+        let game_set: GameSet = SAMPLE.parse().unwrap();
+        
+        assert_eq!(GameSet::from([("red", 1), ("green", 2), ("blue", 6)]), game_set);
+    }
+
+    #[test]
+    fn test_game_parse() {
+        assert_eq!(Game {
+            id: 1,
+            game_sets: vec![
+                GameSet::from([("red", 4), ("blue", 3)]),
+                GameSet::from([("red", 1), ("green", 2), ("blue", 6)]),
+                GameSet::from([("green", 2)])
+            ]
+        }, "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".parse().unwrap())
+    }
+
+    #[test]
+    fn test_game_parse_invalid_color() {
+        assert_eq!(
+            Err(ParseError::unexpected(19, "purple!", "a color name")),
+            "Game 1: 3 blue; 4 purple!, 1 red".parse::<Game>()
+        );
+    }
+
+    #[test]
+    fn test_game_parse_invalid_id() {
+        assert_eq!(
+            Err(ParseError::invalid_number(6, "x")),
+            "Game x: 3 blue".parse::<Game>()
+        );
+    }
+
+    #[test]
+    fn test_game_possible() {
+        let game = Game {
+            id: 42,
+            game_sets: vec![GameSet::from([("red", 2), ("green", 3), ("blue", 4)])]
+        };
+
+        assert!(game.possible(&Bag::default()));
+    }
+
+    #[test]
+    fn test_game_possible_with_max_values() {
+        let game = Game {
+            id: 42,
+            game_sets: vec![GameSet::from([("red", MAX_R), ("green", MAX_G), ("blue", MAX_B)])]
+        };
+
+        assert!(game.possible(&Bag::default()));
+    }
+    
+    #[test]
+    fn test_game_impossible() {
+        let game = Game {
+            id: 42,
+            game_sets: vec![GameSet::from([("red", 42), ("green", 53), ("blue", 64)])]
+        };
+        
+        assert!(!game.possible(&Bag::default()));
+    }
+
+    #[test]
+    fn test_custom_bag() {
+        let bag: Bag = "20 red, 5 yellow".parse().unwrap();
+        let conundrum = Day2::default().parse("Game 1: 3 red, 5 yellow\nGame 2: 6 yellow\nGame 3: 1 red; 1 blue").unwrap();
+        let day2 = Day2 { bag };
+
+        assert_eq!(Ok(1), day2.part1(&conundrum));
+        // games without yellow cubes have no power
+        assert_eq!(Ok(3 * 5), day2.part2(&conundrum));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Err(ParseError::too_large("the number of red cubes").at_line(1).offset(16)), "4294967295 red, 1 red".parse::<GameSet>());
+
+        let conundrum = Day2::default().parse("Game 1: 100000 red, 100000 green, 1 blue").unwrap();
+
+        assert_eq!(Ok(10_000_000_000), Day2::default().part2(&conundrum));
+
+        let conundrum = Day2::default().parse("Game 1: 4294967295 red, 4294967295 green, 2 blue").unwrap();
+
+        assert_eq!(Err(ParseError::too_large("the power")), Day2::default().part2(&conundrum));
+    }
+
+    #[test]
+    fn test_load_bag() {
+        let path = std::env::temp_dir().join(format!("aoc-2023-rs-bag-{}.toml", std::process::id()));
+        std::fs::write(&path, "red = 20\nyellow = 5\n").unwrap();
+
+        assert_eq!(Ok("20 red, 5 yellow".parse().unwrap()), Bag::load(&path));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_first_impossible_draw() {
+        let game: Game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red".parse().unwrap();
+
+        assert_eq!(Some((0, &game.game_sets[0])), game.first_impossible_draw(&Bag::default()));
+        assert_eq!("20 red, 5 yellow", "5 yellow, 20 red".parse::<GameSet>().unwrap().to_string());
+    }
+
+    #[test]
+    fn test_color_stats() {
+        let conundrum = Day2::default().parse(include_str!("bin/day2/day2-sample.txt")).unwrap();
+        let stats = conundrum.color_stats(&Bag::default());
+
+        assert_eq!(vec!["blue", "green", "red"], stats.keys().collect::<Vec<_>>());
+        assert_eq!(ColorStats { max: 20, mean: (4 + 1 + 20 + 14 + 6) as f64 / 5.0 }, stats["red"]);
+    }
+
+    #[test]
+    fn test_solve_streaming() {
+        let input = include_str!("bin/day2/day2-sample.txt");
+
+        assert_eq!(Ok((8, 2286)), Day2::default().solve_streaming(crate::input::records(input.as_bytes())));
+    }
+}
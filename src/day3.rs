@@ -0,0 +1,432 @@
+use crate::{grid::{Grid, Position}, ParseError, Solution};
+
+/// What the characters of a schematic mean.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct SchematicRules {
+    /// The character of empty cells.
+    #[arg(long, default_value_t = '.')]
+    pub blank: char,
+
+    /// The characters that count as symbols. Defaults to anything that is
+    /// neither a digit nor blank.
+    #[arg(long)]
+    pub symbols: Option<String>,
+
+    /// The symbol marking potential gears.
+    #[arg(long, default_value_t = '*')]
+    pub gear_symbol: char,
+
+    /// How many part numbers a gear has to be next to.
+    #[arg(long, default_value_t = 2)]
+    pub gear_part_numbers: usize,
+
+    /// Only counts cells sharing an edge as adjacent, not diagonal ones.
+    #[arg(long)]
+    pub no_diagonals: bool
+}
+
+impl Default for SchematicRules {
+    fn default() -> Self {
+        Self {
+            blank: '.',
+            symbols: None,
+            gear_symbol: '*',
+            gear_part_numbers: 2,
+            no_diagonals: false
+        }
+    }
+}
+
+impl SchematicRules {
+    pub fn is_symbol(&self, character: char) -> bool {
+        match &self.symbols {
+            Some(symbols) => symbols.contains(character),
+            None => !character.is_ascii_digit() && character != self.blank
+        }
+    }
+
+    /// Offsets to the cells considered adjacent to a cell.
+    pub fn adjacency(&self) -> &'static [Position] {
+        if self.no_diagonals {
+            &Position::ORTHOGONAL
+        } else {
+            &Position::ADJACENT
+        }
+    }
+}
+
+/// An engine schematic: digits, symbols and blank cells.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub rules: SchematicRules
+}
+
+impl Schematic {
+    pub fn candidates(&self) -> impl Iterator<Item = Candidate> + '_ {
+        self.grid.positions()
+            .filter(|p| self.is_first_digit(*p))
+            .map(|start| {
+                let positions = self.contiguous_digit_positions(start);
+                let number = self.number(&positions).expect("numbers are checked when parsing");
+                
+                Candidate { positions, number }
+            })
+    }
+    
+    /// The number made by the run of digits at `positions`, failing if it
+    /// doesn't fit in a `u64`.
+    fn number(&self, positions: &[Position]) -> Result<u64, ParseError> {
+        let string: String = positions.iter()
+            .map(|p| self.grid[*p])
+            .collect();
+        let Position(x, y) = positions[0];
+        
+        string.parse()
+            .map_err(|_| ParseError::invalid_number(x as usize + 1, &string).at_line(y as usize + 1))
+    }
+    
+    /// Checks that every run of digits is a number that fits in a `u64`.
+    fn check_numbers(&self) -> Result<(), ParseError> {
+        self.grid.positions()
+            .filter(|p| self.is_first_digit(*p))
+            .try_for_each(|start| self.number(&self.contiguous_digit_positions(start)).map(|_| ()))
+    }
+    
+    pub fn part_numbers(&self) -> impl Iterator<Item = Candidate> + '_ {
+        self.candidates()
+            .filter(|v| self.is_part_number(v))
+    }
+    
+    /// Indexes every part number by the positions of its digits.
+    pub fn part_number_index(&self) -> PartNumberIndex {
+        let part_numbers: Vec<_> = self.part_numbers().collect();
+        let mut owners = self.grid.map(|_| None);
+        
+        for (i, part_number) in part_numbers.iter().enumerate() {
+            for position in &part_number.positions {
+                owners[*position] = Some(i);
+            }
+        }
+        
+        PartNumberIndex { part_numbers, owners }
+    }
+    
+    /// Every gear symbol together with the part numbers next to it, found
+    /// with a single pass over the schematic.
+    pub fn gear_ratios(&self) -> Vec<Gear> {
+        let index = self.part_number_index();
+        
+        self.gears()
+            .map(|position| Gear {
+                position,
+                part_numbers: index.adjacent(position, self.rules.adjacency())
+                    .map(|p| p.number)
+                    .collect()
+            })
+            .collect()
+    }
+    
+    /// Whether the gear is next to as many part numbers as the rules require.
+    pub fn is_gear(&self, gear: &Gear) -> bool {
+        gear.part_numbers.len() == self.rules.gear_part_numbers
+    }
+    
+    /// The product of the gear's part numbers, if it is next to as many as
+    /// the rules require. Fails if the product doesn't fit in a `u64`.
+    pub fn gear_ratio(&self, gear: &Gear) -> Result<Option<u64>, ParseError> {
+        if !self.is_gear(gear) {
+            return Ok(None);
+        }
+        
+        let Position(x, y) = gear.position;
+        
+        gear.part_numbers.iter()
+            .try_fold(1u64, |ratio, number| ratio.checked_mul(*number))
+            .map(Some)
+            .ok_or_else(|| ParseError::too_large("the gear ratio").at_line(y as usize + 1).offset(x as usize))
+    }
+    
+    pub fn gear_ratio_sum(&self) -> Result<u64, ParseError> {
+        self.gear_ratios().iter().try_fold(0u64, |sum, gear| {
+            sum.checked_add(self.gear_ratio(gear)?.unwrap_or(0))
+                .ok_or_else(|| ParseError::too_large("the sum of gear ratios"))
+        })
+    }
+    
+    pub fn part_number_sum(&self) -> Result<u64, ParseError> {
+        self.part_numbers().try_fold(0u64, |sum, part_number| {
+            sum.checked_add(part_number.number)
+                .ok_or_else(|| ParseError::too_large("the sum of part numbers"))
+        })
+    }
+    
+    pub fn gears(&self) -> impl Iterator<Item = Position> + '_ {
+        self.grid.iter()
+            .filter(|(_, c)| **c == self.rules.gear_symbol)
+            .map(|(p, _)| p)
+    }
+    
+    pub fn is_part_number(&self, candidate: &Candidate) -> bool {
+        candidate.positions.iter().any(|p| self.is_part_number_trigger(*p))
+    }
+    
+    pub fn is_part_number_trigger(&self, position: Position) -> bool {
+        self.grid.neighbours(position, self.rules.adjacency())
+            .any(|(_, c)| self.rules.is_symbol(*c))
+    }
+    
+    /// Re-renders the schematic, marking accepted part numbers, rejected
+    /// candidates and valid gears.
+    pub fn annotate(&self, style: AnnotationStyle) -> String {
+        let mut marks = self.grid.map(|_| Mark::None);
+        
+        for candidate in self.candidates() {
+            let mark = if self.is_part_number(&candidate) { Mark::PartNumber } else { Mark::Rejected };
+            
+            for position in &candidate.positions {
+                marks[*position] = mark;
+            }
+        }
+        
+        for gear in self.gear_ratios() {
+            if self.is_gear(&gear) {
+                marks[gear.position] = Mark::Gear;
+            }
+        }
+        
+        let mut output = String::new();
+        
+        for (cells, marks) in self.grid.rows().zip(marks.rows()) {
+            let mut current = Mark::None;
+            
+            for (cell, mark) in cells.iter().zip(marks) {
+                // gears are marked one by one even when they touch
+                if *mark != current || *mark == Mark::Gear {
+                    output.push_str(style.close(current));
+                    output.push_str(style.open(*mark));
+                    current = *mark;
+                }
+                
+                output.push(*cell);
+            }
+            
+            output.push_str(style.close(current));
+            output.push('\n');
+        }
+        
+        output
+    }
+    
+    fn is_digit(&self, position: Position) -> bool {
+        self.grid.get(position).is_some_and(|c| c.is_ascii_digit())
+    }
+    
+    pub fn is_first_digit(&self, position: Position) -> bool {
+        self.is_digit(position) && !self.is_digit(Position(position.x() - 1, position.y()))
+    }
+    
+    /// The positions of the whole run of digits starting at `start`.
+    pub fn contiguous_digit_positions(&self, start: Position) -> Vec<Position> {
+        let y = start.y();
+        
+        (start.x()..)
+            .map(|x| Position(x, y))
+            .take_while(|p| self.is_digit(*p))
+            .collect()
+    }
+}
+
+/// What a cell is marked as by [`Schematic::annotate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    None,
+    PartNumber,
+    Rejected,
+    Gear
+}
+
+/// How [`Schematic::annotate`] marks cells.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationStyle {
+    /// Green part numbers, red rejected candidates and yellow gears.
+    Ansi,
+    /// `[part numbers]`, `(rejected candidates)` and `{gears}`, for when
+    /// colours aren't available.
+    Plain
+}
+
+impl AnnotationStyle {
+    pub fn open(&self, mark: Mark) -> &'static str {
+        match (self, mark) {
+            (_, Mark::None) => "",
+            (AnnotationStyle::Ansi, Mark::PartNumber) => "\x1b[32m",
+            (AnnotationStyle::Ansi, Mark::Rejected) => "\x1b[31m",
+            (AnnotationStyle::Ansi, Mark::Gear) => "\x1b[1;33m",
+            (AnnotationStyle::Plain, Mark::PartNumber) => "[",
+            (AnnotationStyle::Plain, Mark::Rejected) => "(",
+            (AnnotationStyle::Plain, Mark::Gear) => "{"
+        }
+    }
+    
+    pub fn close(&self, mark: Mark) -> &'static str {
+        match (self, mark) {
+            (_, Mark::None) => "",
+            (AnnotationStyle::Ansi, _) => "\x1b[0m",
+            (AnnotationStyle::Plain, Mark::PartNumber) => "]",
+            (AnnotationStyle::Plain, Mark::Rejected) => ")",
+            (AnnotationStyle::Plain, Mark::Gear) => "}"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Candidate {
+    pub positions: Vec<Position>,
+    pub number: u64
+}
+
+/// Which part number, if any, each cell of a schematic belongs to.
+#[derive(Debug, Clone)]
+pub struct PartNumberIndex {
+    pub part_numbers: Vec<Candidate>,
+    pub owners: Grid<Option<usize>>
+}
+
+impl PartNumberIndex {
+    pub fn get(&self, position: Position) -> Option<&Candidate> {
+        self.owners.get(position)
+            .copied()
+            .flatten()
+            .map(|i| &self.part_numbers[i])
+    }
+    
+    /// The distinct part numbers `offsets` away from `position`.
+    pub fn adjacent<'a>(&'a self, position: Position, offsets: &'a [Position]) -> impl Iterator<Item = &'a Candidate> {
+        let mut seen = Vec::new();
+        
+        self.owners.neighbours(position, offsets)
+            .filter_map(|(_, owner)| *owner)
+            .filter(move |i| {
+                let new = !seen.contains(i);
+                seen.push(*i);
+                new
+            })
+            .map(|i| &self.part_numbers[i])
+    }
+}
+
+/// A gear symbol and the part numbers next to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub position: Position,
+    pub part_numbers: Vec<u64>
+}
+
+#[derive(Debug, Default)]
+pub struct Day3 {
+    pub rules: SchematicRules
+}
+
+impl Solution for Day3 {
+    type Input = Schematic;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Schematic, ParseError> {
+        let schematic = Schematic { grid: input.parse()?, rules: self.rules.clone() };
+        schematic.check_numbers()?;
+        
+        Ok(schematic)
+    }
+
+    fn part1(&self, schematic: &Schematic) -> Result<u64, ParseError> {
+        schematic.part_number_sum()
+    }
+
+    fn part2(&self, schematic: &Schematic) -> Result<u64, ParseError> {
+        schematic.gear_ratio_sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG_NUMBERS: &str = include_str!("bin/day3/day3-sample-2.txt");
+
+    #[test]
+    fn test_long_numbers_at_row_edges() {
+        let schematic = Day3::default().parse(LONG_NUMBERS).unwrap();
+        let numbers: Vec<_> = schematic.candidates().map(|c| c.number).collect();
+
+        assert_eq!(vec![123456, 987654, 7777777, 4444, 99999999999], numbers);
+    }
+
+    #[test]
+    fn test_gear_ratios() {
+        let schematic = Day3::default().parse(include_str!("bin/day3/day3-sample.txt")).unwrap();
+        let gears = schematic.gear_ratios();
+
+        assert_eq!(vec![
+            Gear { position: Position(3, 1), part_numbers: vec![467, 35] },
+            Gear { position: Position(3, 4), part_numbers: vec![617] },
+            Gear { position: Position(5, 8), part_numbers: vec![598, 755] }
+        ], gears);
+        assert_eq!(vec![Ok(Some(16345)), Ok(None), Ok(Some(451490))], gears.iter().map(|g| schematic.gear_ratio(g)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_long_part_numbers() {
+        let schematic = Day3::default().parse(LONG_NUMBERS).unwrap();
+
+        assert_eq!(Ok(8893331), Day3::default().part1(&schematic));
+        assert_eq!(Ok(123456 * 7777777), Day3::default().part2(&schematic));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            Err(ParseError::invalid_number(2, "123456789012345678901234").at_line(2)),
+            Day3::default().parse("..........................\n.123456789012345678901234*").map(|_| ())
+        );
+
+        let schematic = Day3::default().parse("4294967296*4294967296.\n......................\n18446744073709551615+1").unwrap();
+
+        assert_eq!(Err(ParseError::too_large("the gear ratio").at_line(1).offset(10)), Day3::default().part2(&schematic));
+        assert_eq!(Err(ParseError::too_large("the sum of part numbers")), Day3::default().part1(&schematic));
+    }
+
+    #[test]
+    fn test_custom_symbols() {
+        let input = "12#...\n......\n34@...\n..56..";
+        let day3 = Day3 { rules: SchematicRules { symbols: Some("@".to_owned()), ..Default::default() } };
+        let schematic = day3.parse(input).unwrap();
+
+        assert_eq!(vec![34, 56], schematic.part_numbers().map(|c| c.number).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_gear_rules() {
+        let input = "1.2\n.x.\n3..";
+        let diagonal = Day3 { rules: SchematicRules { gear_symbol: 'x', gear_part_numbers: 3, ..Default::default() } };
+        let orthogonal = Day3 { rules: SchematicRules { no_diagonals: true, ..diagonal.rules.clone() } };
+
+        assert_eq!(Ok(6), diagonal.part2(&diagonal.parse(input).unwrap()));
+        assert_eq!(Ok(0), orthogonal.part2(&orthogonal.parse(input).unwrap()));
+        assert_eq!(Ok(0), orthogonal.part1(&orthogonal.parse(input).unwrap()));
+    }
+
+    #[test]
+    fn test_annotate() {
+        let schematic = Day3::default().parse(include_str!("bin/day3/day3-sample.txt")).unwrap();
+        let plain = schematic.annotate(AnnotationStyle::Plain);
+        let lines: Vec<_> = plain.lines().collect();
+
+        assert_eq!("[467]..(114)..", lines[0]);
+        assert_eq!("...{*}......", lines[1]);
+        assert_eq!("[617]*......", lines[4]);
+        assert_eq!(".....+.(58).", lines[5]);
+        assert!(schematic.annotate(AnnotationStyle::Ansi).contains("\x1b[32m467\x1b[0m..\x1b[31m114\x1b[0m.."));
+    }
+}
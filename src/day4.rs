@@ -0,0 +1,388 @@
+use std::{collections::{HashMap, VecDeque}, str::FromStr};
+
+use crate::{error::{column_of, end_column, parse_lines_with, parse_number}, ParseError, Solution};
+
+/// How numbers that are on a side of a card more than once count.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scoring {
+    /// Numbers may not repeat on a side, and each one matches at most once.
+    #[default]
+    Set,
+    /// Numbers may repeat, and match as many times as they are on both sides.
+    Multiset
+}
+
+/// The numbers on one side of a card, along with how many times each of them
+/// is there.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Numbers {
+    counts: HashMap<u32, u32>
+}
+
+impl Numbers {
+    pub fn count(&self, number: u32) -> u32 {
+        self.counts.get(&number).copied().unwrap_or_default()
+    }
+    
+    /// Adds `number`, returning how many times it is there now.
+    pub fn insert(&mut self, number: u32) -> u32 {
+        let count = self.counts.entry(number).or_default();
+        *count += 1;
+        *count
+    }
+    
+    /// Every distinct number along with how many times it is there.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.counts.iter().map(|(number, count)| (*number, *count))
+    }
+}
+
+impl FromIterator<u32> for Numbers {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut numbers = Numbers::default();
+        iter.into_iter().for_each(|number| { numbers.insert(number); });
+        numbers
+    }
+}
+
+impl<const N: usize> From<[u32; N]> for Numbers {
+    fn from(value: [u32; N]) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning_numbers: Numbers,
+    pub my_numbers: Numbers
+}
+
+impl Card {
+    /// Parses a card, rejecting repeated numbers unless `scoring` counts them.
+    pub fn parse_with(value: &str, scoring: Scoring) -> Result<Self, ParseError> {
+        let Some((head, numbers_str)) = value.split_once(':') else {
+            return Err(ParseError::missing(end_column(value), "\":\" after the card id"));
+        };
+        
+        let mut words = head.split_whitespace();
+        
+        match words.next() {
+            Some("Card") => {}
+            Some(other) => return Err(ParseError::unexpected(column_of(value, other), other, "\"Card <id>\"")),
+            None => return Err(ParseError::missing(1, "\"Card <id>\""))
+        }
+        
+        let Some(id_str) = words.next() else { return Err(ParseError::missing(end_column(head), "card id")) };
+        
+        if let Some(extra) = words.next() {
+            return Err(ParseError::unexpected(column_of(value, extra), extra, "\":\" after the card id"));
+        }
+        
+        let Some((winning_numbers_str, my_numbers_str)) = numbers_str.split_once('|') else {
+            return Err(ParseError::missing(end_column(value), "\"|\" between the winning numbers and my numbers"));
+        };
+        
+        Ok(Self {
+            id: parse_number(value, id_str)?,
+            winning_numbers: parse_numbers(value, winning_numbers_str, scoring)?,
+            my_numbers: parse_numbers(value, my_numbers_str, scoring)?
+        })
+    }
+    
+    /// How many of my numbers match winning numbers.
+    pub fn winning_number_count(&self, scoring: Scoring) -> u32 {
+        self.winning_numbers.iter()
+            .map(|(number, count)| match scoring {
+                Scoring::Set => self.my_numbers.count(number).min(1),
+                Scoring::Multiset => self.my_numbers.count(number).min(count)
+            })
+            .sum()
+    }
+    
+    /// One point for the first match, doubled for every other one. Fails
+    /// for cards with more than 64 matches, whose score doesn't fit in a u64.
+    pub fn score(&self, scoring: Scoring) -> Result<u64, ParseError> {
+        match self.winning_number_count(scoring) {
+            0 => Ok(0),
+            count => 1u64.checked_shl(count - 1)
+                .ok_or_else(|| ParseError::too_large(format!("the score of card {}", self.id)))
+        }
+    }
+}
+
+/// Parses the numbers on one side of a card.
+fn parse_numbers(line: &str, numbers: &str, scoring: Scoring) -> Result<Numbers, ParseError> {
+    let mut parsed = Numbers::default();
+    
+    for number in numbers.split_whitespace() {
+        if parsed.insert(parse_number(line, number)?) > 1 && scoring == Scoring::Set {
+            return Err(ParseError::unexpected(column_of(line, number), number, "a number that isn't on this side yet"));
+        }
+    }
+    
+    Ok(parsed)
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53    
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        Card::parse_with(value, Scoring::default())
+    }
+}
+
+/// Every card along with the copies of it you end up with.
+///
+/// A card wins one copy of each of the next cards for each of its winning
+/// numbers. Copies that would go past the last card are lost.
+#[derive(Debug, Clone)]
+pub struct CardTable {
+    pub cards: Vec<Card>,
+    pub scoring: Scoring,
+    wins: Vec<u32>,
+    /// The copies of each card, or `None` if there are too many to count.
+    /// Only part 2 needs them, so it is left to report that.
+    copies: Vec<Option<u64>>
+}
+
+impl CardTable {
+    pub fn new(cards: Vec<Card>, scoring: Scoring) -> Self {
+        let wins: Vec<_> = cards.iter().map(|card| card.winning_number_count(scoring)).collect();
+        let mut copies = vec![Some(1u64); cards.len()];
+        
+        for i in 0..cards.len() {
+            let won = copies[i];
+            let end = (i + 1 + wins[i] as usize).min(cards.len());
+            
+            for other in &mut copies[(i + 1)..end] {
+                *other = other.zip(won).and_then(|(other, won)| other.checked_add(won));
+            }
+        }
+        
+        Self { cards, scoring, wins, copies }
+    }
+    
+    /// How many copies of the card at `index` you end up with, failing if
+    /// there are too many to count.
+    fn copies(&self, index: usize) -> Result<u64, ParseError> {
+        self.copies[index]
+            .ok_or_else(|| ParseError::too_large(format!("the number of copies of card {}", self.cards[index].id)))
+    }
+    
+    /// How each card did, in order, failing at the first card whose copies or
+    /// points are too large to count.
+    pub fn rows(&self) -> impl Iterator<Item = Result<CardRow<'_>, ParseError>> {
+        self.cards.iter()
+            .zip(&self.wins)
+            .enumerate()
+            .map(|(i, (card, wins))| Ok(CardRow {
+                card,
+                wins: *wins,
+                copies: self.copies(i)?,
+                points: card.score(self.scoring)?
+            }))
+    }
+    
+    pub fn total_points(&self) -> Result<u64, ParseError> {
+        self.cards.iter().try_fold(0u64, |sum, card| {
+            sum.checked_add(card.score(self.scoring)?)
+                .ok_or_else(|| ParseError::too_large("the sum of scores"))
+        })
+    }
+    
+    pub fn total_copies(&self) -> Result<u64, ParseError> {
+        (0..self.cards.len()).try_fold(0u64, |sum, i| {
+            sum.checked_add(self.copies(i)?)
+                .ok_or_else(|| ParseError::too_large("the sum of copies"))
+        })
+    }
+}
+
+impl FromStr for CardTable {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        Day4::default().parse(input)
+    }
+}
+
+/// A single card of a [`CardTable`] and how it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CardRow<'a> {
+    pub card: &'a Card,
+    /// How many of its numbers are winning numbers.
+    pub wins: u32,
+    /// How many copies of it you end up with, the original included.
+    pub copies: u64,
+    pub points: u64
+}
+
+#[derive(Debug, Default)]
+pub struct Day4 {
+    pub scoring: Scoring
+}
+
+impl Day4 {
+    /// Works out both parts as the cards are read, in a single pass. Only the
+    /// copies won for cards that haven't been read yet are kept around, which
+    /// are never more than a card has winning numbers.
+    pub fn solve_streaming(&self, cards: impl Iterator<Item = Result<Card, ParseError>>) -> Result<(u64, u64), ParseError> {
+        let mut pending = VecDeque::new();
+        let mut scores = 0u64;
+        let mut total = 0u64;
+        
+        for card in cards {
+            let card = card?;
+            let copies = pending.pop_front().unwrap_or(0u64).checked_add(1)
+                .ok_or_else(|| ParseError::too_large(format!("the number of copies of card {}", card.id)))?;
+            let count = card.winning_number_count(self.scoring) as usize;
+            
+            if pending.len() < count {
+                pending.resize(count, 0);
+            }
+            
+            // the copies are only counted once their card is read, so an
+            // overflow here would be reported there
+            for other in pending.iter_mut().take(count) {
+                *other = other.saturating_add(copies);
+            }
+            
+            scores = scores.checked_add(card.score(self.scoring)?)
+                .ok_or_else(|| ParseError::too_large("the sum of scores"))?;
+            total = total.checked_add(copies)
+                .ok_or_else(|| ParseError::too_large("the sum of copies"))?;
+        }
+        
+        Ok((scores, total))
+    }
+}
+
+impl Solution for Day4 {
+    type Input = CardTable;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<CardTable, ParseError> {
+        let cards = parse_lines_with(input, |line| Card::parse_with(line, self.scoring))?;
+        
+        Ok(CardTable::new(cards, self.scoring))
+    }
+
+    fn part1(&self, table: &CardTable) -> Result<u64, ParseError> {
+        table.total_points()
+    }
+
+    fn part2(&self, table: &CardTable) -> Result<u64, ParseError> {
+        table.total_copies()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::records;
+
+    #[test]
+    fn test_streaming_matches_parsed() {
+        for input in [
+            include_str!("bin/day4/day4-sample.txt"),
+            include_str!("bin/day4/day4-jc.txt")
+        ] {
+            let table = Day4::default().parse(input).unwrap();
+
+            assert_eq!(Ok((Day4::default().part1(&table).unwrap(), Day4::default().part2(&table).unwrap())), Day4::default().solve_streaming(records(input.as_bytes())));
+        }
+    }
+
+    #[test]
+    fn test_card_parse() {
+        let card = Card {
+            id: 100,
+            winning_numbers: Numbers::from([41, 48]),
+            my_numbers: Numbers::from([83, 41, 6])
+        };
+
+        assert_eq!(Ok(card.clone()), "Card 100: 41 48 | 83 41  6".parse());
+        assert_eq!(Ok(card.clone()), "Card 100:41 48|83 41 6".parse());
+        assert_eq!(Ok(card), "Card\t 100 :  41\t48 |  83 41 6  ".parse());
+    }
+
+    #[test]
+    fn test_card_parse_errors() {
+        assert_eq!(
+            Err(ParseError::unexpected(20, "48", "a number that isn't on this side yet")),
+            "Card 1: 41 48 | 48 48 1".parse::<Card>()
+        );
+        assert_eq!(Err(ParseError::unexpected(1, "Cart", "\"Card <id>\"")), "Cart 1: 1 | 2".parse::<Card>());
+        assert_eq!(Err(ParseError::invalid_number(6, "x")), "Card x: 1 | 2".parse::<Card>());
+        assert_eq!(Err(ParseError::unexpected(8, "2", "\":\" after the card id")), "Card 1 2: 1 | 2".parse::<Card>());
+        assert_eq!(Err(ParseError::missing(12, "\"|\" between the winning numbers and my numbers")), "Card 1: 1 2".parse::<Card>());
+    }
+
+    #[test]
+    fn test_card_table_rows() {
+        let table: CardTable = include_str!("bin/day4/day4-sample.txt").parse().unwrap();
+        let rows: Vec<_> = table.rows().map(|row| row.map(|row| (row.card.id, row.wins, row.copies, row.points)).unwrap()).collect();
+
+        assert_eq!(vec![
+            (1, 4, 1, 8),
+            (2, 2, 2, 2),
+            (3, 2, 4, 2),
+            (4, 1, 8, 1),
+            (5, 0, 14, 0),
+            (6, 0, 1, 0)
+        ], rows);
+    }
+
+    #[test]
+    fn test_card_table_clamps_copies() {
+        let table: CardTable = "Card   1:  1  2 |  1  9\nCard   2:  1  2 |  1  2".parse().unwrap();
+
+        assert_eq!(vec![1, 2], table.rows().map(|row| row.unwrap().copies).collect::<Vec<_>>());
+        assert_eq!(Ok(3), table.total_copies());
+    }
+
+    #[test]
+    fn test_copies_overflow() {
+        let input: String = (1..=100).map(|id| format!("Card {}: 1 2 3 4 5 6 7 8 9 10 | 1 2 3 4 5 6 7 8 9 10\n", id)).collect();
+
+        let table = Day4::default().parse(&input).unwrap();
+
+        assert_eq!(Ok(100 * (1 << 9)), Day4::default().part1(&table));
+        // the sum overflows before the copies of a single card do
+        assert_eq!(Err(ParseError::too_large("the sum of copies")), Day4::default().part2(&table));
+        assert_eq!(Err(ParseError::too_large("the sum of copies")), Day4::default().solve_streaming(records(input.as_bytes())));
+        assert_eq!(Err(ParseError::too_large("the number of copies of card 66")), table.rows().try_for_each(|row| row.map(|_| ())));
+    }
+
+    #[test]
+    fn test_score_overflow() {
+        let line = |matches: usize| {
+            let numbers = vec!["7"; matches].join(" ");
+            format!("Card 3: {} | {}", numbers, numbers)
+        };
+        let card = |matches| Card::parse_with(&line(matches), Scoring::Multiset).unwrap();
+
+        assert_eq!(Ok(1 << 63), card(64).score(Scoring::Multiset));
+        assert_eq!(Err(ParseError::too_large("the score of card 3")), card(65).score(Scoring::Multiset));
+
+        let day4 = Day4 { scoring: Scoring::Multiset };
+        let table = day4.parse(&line(65)).unwrap();
+
+        assert_eq!(Err(ParseError::too_large("the score of card 3")), day4.part1(&table));
+        assert_eq!(Ok(1), day4.part2(&table));
+    }
+
+    #[test]
+    fn test_multiset_scoring() {
+        let line = "Card 1: 5 5 5 7 | 5 5 7 7 9";
+        let card = Card::parse_with(line, Scoring::Multiset).unwrap();
+
+        assert!(Card::parse_with(line, Scoring::Set).is_err());
+        assert_eq!(2, card.winning_number_count(Scoring::Set));
+        assert_eq!(3, card.winning_number_count(Scoring::Multiset));
+        assert_eq!(Ok(4), card.score(Scoring::Multiset));
+    }
+}
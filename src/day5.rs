@@ -0,0 +1,317 @@
+use std::cmp::{max, min};
+use std::fmt::Debug;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::{error::{column_of, end_column, parse_number}, ParseError, Solution};
+
+#[derive(Debug)]
+pub struct ConversionMapEntry {
+    pub source: Range<i64>,
+    pub target: Range<i64>
+}
+
+impl ConversionMapEntry {
+    pub fn offset(&self) -> i64 {
+        self.target.start - self.source.start
+    }
+}
+
+pub trait ConversionStep: Debug {
+    fn lookup(&self, index: i64) -> i64;
+
+    /// Looks up every index in `range` at once, returning the ranges they end
+    /// up in. The returned ranges are in no particular order.
+    fn lookup_range(&self, range: Range<i64>) -> Vec<Range<i64>>;
+}
+
+#[derive(Debug)]
+pub struct ConversionMapByRange {
+    pub next_step: Arc<dyn ConversionStep + Sync + Send>,
+    pub entries: Vec<ConversionMapEntry>
+}
+
+impl ConversionMapByRange {
+    pub fn new() -> Self {
+        Self {
+            next_step: Arc::new(ConversionMapDummy),
+            entries: Vec::new()
+        }
+    }
+
+    pub fn with_next_step(self, next_step: Arc<dyn ConversionStep + Sync + Send>) -> Self {
+        Self { next_step, ..self }
+    }
+}
+
+impl Default for ConversionMapByRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConversionStep for ConversionMapByRange {
+    fn lookup(&self, index: i64) -> i64 {
+        for entry in &self.entries {
+            if entry.source.contains(&index) {
+                return self.next_step.lookup(index + entry.offset());
+            }
+        }
+
+        self.next_step.lookup(index)
+    }
+
+    fn lookup_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        let mut unmapped = vec![range];
+        let mut mapped = Vec::new();
+
+        // split off the part of each range that every entry covers, leaving
+        // the rest for the following entries
+        for entry in &self.entries {
+            let mut remaining = Vec::new();
+
+            for range in unmapped {
+                let start = max(range.start, entry.source.start);
+                let end = min(range.end, entry.source.end);
+
+                if start >= end {
+                    remaining.push(range);
+                    continue;
+                }
+
+                mapped.push((start + entry.offset())..(end + entry.offset()));
+
+                if range.start < start {
+                    remaining.push(range.start..start);
+                }
+
+                if end < range.end {
+                    remaining.push(end..range.end);
+                }
+            }
+
+            unmapped = remaining;
+        }
+
+        mapped.into_iter()
+            .chain(unmapped)
+            .flat_map(|range| self.next_step.lookup_range(range))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct ConversionMapDummy;
+
+impl ConversionStep for ConversionMapDummy {
+    fn lookup(&self, index: i64) -> i64 {
+        index
+    }
+
+    fn lookup_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        vec![range]
+    }
+}
+
+#[derive(Debug)]
+pub struct ConversionMapper {
+    pub seeds: Vec<i64>,
+    pub top: Arc<ConversionMapByRange>,
+    /// The line the seeds are on and its number, to point at in errors.
+    seeds_line: (usize, String)
+}
+
+impl ConversionMapper {
+    pub fn lookup(&self, index: i64) -> i64 {
+        self.top.lookup(index)
+    }
+
+    pub fn lookup_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        self.top.lookup_range(range)
+    }
+
+    /// The seed ranges described by the seed list in part 2, which reads the
+    /// seeds as pairs of a start and a length.
+    pub fn seed_ranges(&self) -> Result<Vec<Range<i64>>, ParseError> {
+        let (line_number, line) = &self.seeds_line;
+
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(ParseError::missing(end_column(line), "a range length for the last seed").at_line(*line_number));
+        }
+
+        self.seeds.chunks_exact(2)
+            .zip(line["seeds: ".len()..].split(' ').skip(1).step_by(2))
+            .map(|(v, length)| match v[1] {
+                ..=0 => Err(ParseError::unexpected(column_of(line, length), length, "a range length above 0").at_line(*line_number)),
+                _ => Ok(v[0]..(v[0] + v[1]))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for ConversionMapper {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        let mut lines = value.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, v)| !v.is_empty());
+        let Some((seeds_line_number, seeds_line)) = lines.next() else { return Err(ParseError::missing(1, "seed list")) };
+        let Some(seed_list) = seeds_line.strip_prefix("seeds: ") else {
+            return Err(ParseError::unexpected(1, seeds_line, "\"seeds: <seeds>\"").at_line(seeds_line_number));
+        };
+        let seeds = seed_list.split(' ')
+            .map(|v| parse_number(seeds_line, v))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.at_line(seeds_line_number))?;
+
+        let mut humidity_to_location = ConversionMapByRange::new();
+        let mut temperature_to_humidity = ConversionMapByRange::new();
+        let mut light_to_temperature = ConversionMapByRange::new();
+        let mut water_to_light = ConversionMapByRange::new();
+        let mut fertilizer_to_water = ConversionMapByRange::new();
+        let mut soil_to_fertilizer = ConversionMapByRange::new();
+        let mut seed_to_soil = ConversionMapByRange::new();
+
+        // filler default value
+        let mut conversion_map = &mut humidity_to_location;
+
+        for (line_number, line) in lines {
+            if line.ends_with("map:") {
+                conversion_map = match line {
+                    "humidity-to-location map:" => &mut humidity_to_location,
+                    "temperature-to-humidity map:" => &mut temperature_to_humidity,
+                    "light-to-temperature map:" => &mut light_to_temperature,
+                    "water-to-light map:" => &mut water_to_light,
+                    "fertilizer-to-water map:" => &mut fertilizer_to_water,
+                    "soil-to-fertilizer map:" => &mut soil_to_fertilizer,
+                    "seed-to-soil map:" => &mut seed_to_soil,
+                    other => return Err(ParseError::unexpected(1, other, "a known map header").at_line(line_number))
+                }
+            } else {
+                let &[data1, data2, data3] = line.split(' ').collect::<Vec<_>>().as_slice() else {
+                    return Err(ParseError::unexpected(1, line, "\"<destination> <source> <length>\"").at_line(line_number));
+                };
+
+                let parse = |data| parse_number::<i64>(line, data).map_err(|e| e.at_line(line_number));
+                let dst = parse(data1)?;
+                let src = parse(data2)?;
+                let len = parse(data3)?;
+
+                conversion_map.entries.push(ConversionMapEntry {
+                    source: src..(src + len),
+                    target: dst..(dst + len)
+                });
+            }
+        }
+
+        let humidity_to_location = Arc::new(humidity_to_location);
+        let temperature_to_location = Arc::new(temperature_to_humidity.with_next_step(humidity_to_location));
+        let light_to_location = Arc::new(light_to_temperature.with_next_step(temperature_to_location));
+        let water_to_location = Arc::new(water_to_light.with_next_step(light_to_location));
+        let fertilizer_to_location = Arc::new(fertilizer_to_water.with_next_step(water_to_location));
+        let soil_to_fertilizer = Arc::new(soil_to_fertilizer.with_next_step(fertilizer_to_location));
+        let seed_to_location = Arc::new(seed_to_soil.with_next_step(soil_to_fertilizer));
+
+        Ok(Self {
+            seeds,
+            top: seed_to_location,
+            seeds_line: (seeds_line_number, seeds_line.to_owned())
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Day5 {
+    /// Looks up every seed of part 2 one by one instead of splitting ranges.
+    pub brute_force: bool
+}
+
+impl Solution for Day5 {
+    type Input = ConversionMapper;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(&self, input: &str) -> Result<ConversionMapper, ParseError> {
+        input.parse()
+    }
+
+    fn part1(&self, mapper: &ConversionMapper) -> Result<i64, ParseError> {
+        Ok(mapper.seeds.iter().map(|v| mapper.lookup(*v)).min().unwrap())
+    }
+
+    fn part2(&self, mapper: &ConversionMapper) -> Result<i64, ParseError> {
+        if self.brute_force {
+            return Ok(mapper.seed_ranges()?
+                .into_iter()
+                .flatten()
+                .map(|i| mapper.lookup(i))
+                .min()
+                .unwrap());
+        }
+
+        Ok(mapper.seed_ranges()?
+            .into_iter()
+            .flat_map(|range| mapper.lookup_range(range))
+            .filter(|range| !range.is_empty())
+            .map(|range| range.start)
+            .min()
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("bin/day5/day5-sample.txt");
+
+    #[test]
+    fn test_lookup_range_splits_on_entry_boundaries() {
+        let mut map = ConversionMapByRange::new();
+        map.entries.push(ConversionMapEntry { source: 98..100, target: 50..52 });
+        map.entries.push(ConversionMapEntry { source: 50..98, target: 52..100 });
+
+        let mut ranges = map.lookup_range(40..99);
+        ranges.sort_by_key(|r| r.start);
+
+        assert_eq!(vec![40..50, 50..51, 52..100], ranges);
+    }
+
+    #[test]
+    fn test_lookup_range_matches_lookup() {
+        let mapper: ConversionMapper = SAMPLE.parse().unwrap();
+
+        for range in mapper.seed_ranges().unwrap() {
+            let mut expected: Vec<_> = range.clone().map(|i| mapper.lookup(i)).collect();
+            let mut actual: Vec<_> = mapper.lookup_range(range).into_iter().flatten().collect();
+            expected.sort();
+            actual.sort();
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_part2_matches_brute_force() {
+        let mapper = Day5::default().parse(SAMPLE).unwrap();
+
+        assert_eq!(Ok(46), Day5::default().part2(&mapper));
+        assert_eq!(Ok(46), Day5 { brute_force: true }.part2(&mapper));
+    }
+
+    #[test]
+    fn test_seed_ranges_errors() {
+        let maps = "\nseed-to-soil map:\n50 98 2\n";
+        let odd = Day5::default().parse(&format!("seeds: 79 14 5{}", maps)).unwrap();
+        let empty = Day5::default().parse(&format!("seeds: 79 0{}", maps)).unwrap();
+
+        // part 1 reads the seeds one by one, so only part 2 minds
+        assert_eq!(Ok(5), Day5::default().part1(&odd));
+        assert_eq!(Err(ParseError::missing(15, "a range length for the last seed")), Day5::default().part2(&odd));
+        assert_eq!(Err(ParseError::unexpected(11, "0", "a range length above 0")), Day5::default().part2(&empty));
+        assert_eq!(Err(ParseError::unexpected(11, "0", "a range length above 0")), Day5 { brute_force: true }.part2(&empty));
+    }
+}
@@ -0,0 +1,467 @@
+use std::{cmp::Ordering, io::BufRead};
+
+use crate::{error::{column_of, end_column, parse_number}, input::exit_on_read_error, ParseError, Solution};
+
+#[derive(Debug, Eq)]
+#[repr(transparent)]
+pub struct RaceResult {
+    pub distance: u64
+}
+
+impl PartialOrd<u64> for RaceResult {
+    fn partial_cmp(&self, other: &u64) -> Option<Ordering> {
+        Some(self.distance.cmp(other))
+    }
+}
+
+impl PartialEq<u64> for RaceResult {
+    fn eq(&self, other: &u64) -> bool {
+        self.distance == *other
+    }
+}
+
+impl PartialEq for RaceResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+pub fn simulate_once(charge_time: u64, travel_time: u64) -> RaceResult {
+    let speed = charge_time;
+    let distance = speed * travel_time;
+    
+    RaceResult { distance }
+}
+
+pub fn simulate(total_time: u64) -> Vec<RaceResult> {
+    (1..total_time)
+        .map(|charge_time| simulate_once(charge_time, total_time - charge_time))
+        .collect()
+}
+
+pub fn find_winning_records(total_time: u64, record: u64) -> Vec<RaceResult> {
+    simulate(total_time).into_iter()
+        .filter(|v| v > &record)
+        .collect()
+}
+
+/// Counts the charge times that beat `record` without simulating them.
+///
+/// A charge time `t` wins when `t * (total_time - t) > record`, which holds
+/// between the two roots of `t^2 - total_time * t + record`. The integer square
+/// root only gets close to the lower root, so the bound is nudged until it is
+/// exact; the winning charge times are symmetric around `total_time / 2`.
+pub fn count_winning_charge_times(total_time: u64, record: u64) -> u64 {
+    let time = total_time as u128;
+    let record = record as u128;
+    let wins = |t: u128| t * (time - t) > record;
+
+    let Some(discriminant) = (time * time).checked_sub(4 * record) else { return 0 };
+    let mut lowest = (time - discriminant.isqrt()) / 2;
+
+    while lowest <= time / 2 && !wins(lowest) {
+        lowest += 1;
+    }
+
+    while lowest > 0 && wins(lowest - 1) {
+        lowest -= 1;
+    }
+
+    if lowest > time / 2 {
+        return 0;
+    }
+
+    (time - 2 * lowest + 1) as u64
+}
+
+/// A race's duration together with the record distance to beat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64
+}
+
+impl Race {
+    /// How many charge times beat the record.
+    pub fn ways_to_win(&self) -> u64 {
+        count_winning_charge_times(self.time, self.record)
+    }
+
+    /// Like [`Race::ways_to_win`], but simulates every charge time.
+    pub fn simulated_ways_to_win(&self) -> u64 {
+        find_winning_records(self.time, self.record).len() as u64
+    }
+}
+
+/// One row of the input, read both as separate numbers and with the spaces
+/// between them kerned away. The kerned number only matters to part 2, so a
+/// row whose kerned number doesn't fit keeps the error for part 2 to return.
+struct Row {
+    numbers: Vec<u64>,
+    kerned: Result<u64, ParseError>
+}
+
+/// Parses a row like `Time:      7  15   30`.
+fn parse_row(line: Option<&str>, line_number: usize, label: &str) -> Result<Row, ParseError> {
+    let Some(line) = line else { return Err(ParseError::missing(1, format!("{:?} row", label)).at_line(line_number)) };
+    let Some(numbers) = line.strip_prefix(label) else {
+        return Err(ParseError::unexpected(1, line, format!("{:?} row", label)).at_line(line_number));
+    };
+    
+    let parts: Vec<_> = numbers.split(' ')
+        .filter(|v| !v.is_empty())
+        .collect();
+    let Some(first) = parts.first() else {
+        return Err(ParseError::missing(end_column(line), "numbers").at_line(line_number));
+    };
+    
+    let numbers = parts.iter()
+        .map(|v| parse_number(line, v))
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.at_line(line_number))?;
+    
+    let digits = parts.concat();
+    let kerned = digits.parse()
+        .map_err(|_| ParseError::invalid_number(column_of(line, first), &digits).at_line(line_number));
+    
+    Ok(Row { numbers, kerned })
+}
+
+/// Reads a sheet one word at a time, for sheets with rows too long to keep
+/// in memory.
+struct Words<R> {
+    reader: R,
+    line: usize,
+    column: usize
+}
+
+impl<R: BufRead> Words<R> {
+    fn new(reader: R) -> Self {
+        Self { reader, line: 1, column: 1 }
+    }
+    
+    fn peek(&mut self) -> Option<u8> {
+        self.reader.fill_buf().unwrap_or_else(|e| exit_on_read_error("the input", e)).first().copied()
+    }
+    
+    fn bump(&mut self) {
+        // columns count characters, so bytes that continue one don't move on
+        if !matches!(self.peek(), Some(0x80..=0xbf)) {
+            self.column += 1;
+        }
+        
+        self.reader.consume(1);
+    }
+    
+    /// The next word on the current row along with its column, if there is
+    /// one.
+    fn next_word(&mut self) -> Option<(usize, String)> {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r')) {
+            self.bump();
+        }
+        
+        let column = self.column;
+        let mut word = Vec::new();
+        
+        while let Some(byte) = self.peek().filter(|b| !b" \t\r\n".contains(b)) {
+            word.push(byte);
+            self.bump();
+        }
+        
+        (!word.is_empty()).then(|| (column, String::from_utf8_lossy(&word).into_owned()))
+    }
+    
+    /// Skips the rest of the current row.
+    fn next_row(&mut self) {
+        while let Some(byte) = self.peek() {
+            self.bump();
+            
+            if byte == b'\n' {
+                break;
+            }
+        }
+        
+        self.line += 1;
+        self.column = 1;
+    }
+    
+    fn label(&mut self, label: &str) -> Result<(), ParseError> {
+        match self.next_word() {
+            Some((_, word)) if word == label => Ok(()),
+            Some((column, word)) => Err(ParseError::unexpected(column, &word, format!("{:?} row", label)).at_line(self.line)),
+            None => Err(ParseError::missing(self.column, format!("{:?} row", label)).at_line(self.line))
+        }
+    }
+    
+    fn next_number(&mut self) -> Option<Result<(usize, u64), ParseError>> {
+        let (column, word) = self.next_word()?;
+        
+        Some(word.parse()
+            .map(|number| (column, number))
+            .map_err(|_| ParseError::invalid_number(column, &word).at_line(self.line)))
+    }
+    
+    /// Reads the rest of the row as a single kerned number.
+    fn kerned_number(&mut self) -> Result<u64, ParseError> {
+        let mut number: Option<u64> = None;
+        
+        while let Some((column, word)) = self.next_word() {
+            for byte in word.bytes() {
+                let digit = (byte as char).to_digit(10)
+                    .ok_or_else(|| ParseError::invalid_number(column, &word).at_line(self.line))?;
+                
+                number = number.unwrap_or_default().checked_mul(10)
+                    .and_then(|n| n.checked_add(digit as u64))
+                    .ok_or_else(|| ParseError::invalid_number(column, &word).at_line(self.line))
+                    .map(Some)?;
+            }
+        }
+        
+        number.ok_or_else(|| ParseError::missing(self.column, "numbers").at_line(self.line))
+    }
+}
+
+/// Every race on the sheet, along with the single race you get by ignoring
+/// the spaces between the numbers, if its numbers fit in a u64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Races {
+    pub races: Vec<Race>,
+    pub kerned: Result<Race, ParseError>
+}
+
+#[derive(Debug, Default)]
+pub struct Day6 {
+    /// Simulates every charge time instead of solving for the winning ones.
+    pub simulate: bool,
+    /// Reads the sheet as a single kerned race in part 1 as well.
+    pub kerning: bool
+}
+
+impl Day6 {
+    fn ways_to_win(&self, race: &Race) -> u64 {
+        if self.simulate {
+            race.simulated_ways_to_win()
+        } else {
+            race.ways_to_win()
+        }
+    }
+
+    /// Multiplies `product` by the ways to win `race`, failing if the result
+    /// doesn't fit in a u64.
+    fn multiply_ways_to_win(&self, product: u64, race: &Race) -> Result<u64, ParseError> {
+        product.checked_mul(self.ways_to_win(race))
+            .ok_or_else(|| ParseError::too_large("the product of the ways to win"))
+    }
+
+    /// The races part 1 is about.
+    pub fn races<'a>(&self, races: &'a Races) -> Result<&'a [Race], ParseError> {
+        if self.kerning {
+            races.kerned.as_ref().map(std::slice::from_ref).map_err(Clone::clone)
+        } else {
+            Ok(&races.races)
+        }
+    }
+}
+
+impl Day6 {
+    /// Like [`Solution::part1`], but reads the sheet bit by bit. Only the
+    /// times are kept until the distances are read, not the text of the
+    /// sheet.
+    pub fn part1_streaming(&self, reader: impl BufRead) -> Result<u64, ParseError> {
+        if self.kerning {
+            return self.part2_streaming(reader);
+        }
+        
+        let mut words = Words::new(reader);
+        
+        words.label("Time:")?;
+        let times = std::iter::from_fn(|| words.next_number())
+            .map(|number| number.map(|(_, time)| time))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        if times.is_empty() {
+            return Err(ParseError::missing(words.column, "numbers").at_line(1));
+        }
+        
+        words.next_row();
+        words.label("Distance:")?;
+        
+        let expected = format!("{} distances", times.len());
+        let mut product = 1;
+        let mut count = 0;
+        
+        while let Some(number) = words.next_number() {
+            let (column, record) = number?;
+            let Some(&time) = times.get(count) else {
+                return Err(ParseError::unexpected(column, &record.to_string(), expected).at_line(2));
+            };
+            
+            product = self.multiply_ways_to_win(product, &Race { time, record })
+                .map_err(|e| e.at_line(2).offset(column - 1))?;
+            count += 1;
+        }
+        
+        if count != times.len() {
+            return Err(ParseError::missing(words.column, expected).at_line(2));
+        }
+        
+        Ok(product)
+    }
+    
+    /// Like [`Solution::part2`], but reads the sheet bit by bit.
+    pub fn part2_streaming(&self, reader: impl BufRead) -> Result<u64, ParseError> {
+        let mut words = Words::new(reader);
+        
+        words.label("Time:")?;
+        let time = words.kerned_number()?;
+        
+        words.next_row();
+        words.label("Distance:")?;
+        let record = words.kerned_number()?;
+        
+        Ok(self.ways_to_win(&Race { time, record }))
+    }
+}
+
+impl Solution for Day6 {
+    type Input = Races;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Races, ParseError> {
+        let mut lines = input.lines();
+        let times = parse_row(lines.next(), 1, "Time:")?;
+        let distances = parse_row(lines.next(), 2, "Distance:")?;
+        
+        if times.numbers.len() != distances.numbers.len() {
+            let line = input.lines().nth(1).unwrap();
+            return Err(ParseError::unexpected(1, line, format!("{} distances", times.numbers.len())).at_line(2));
+        }
+        
+        Ok(Races {
+            races: times.numbers.into_iter().zip(distances.numbers)
+                .map(|(time, record)| Race { time, record })
+                .collect(),
+            kerned: times.kerned.and_then(|time| Ok(Race { time, record: distances.kerned? }))
+        })
+    }
+
+    fn part1(&self, races: &Races) -> Result<u64, ParseError> {
+        self.races(races)?.iter()
+            .try_fold(1, |product, race| self.multiply_ways_to_win(product, race))
+    }
+
+    fn part2(&self, races: &Races) -> Result<u64, ParseError> {
+        let race = races.kerned.as_ref().map_err(Clone::clone)?;
+        
+        Ok(self.ways_to_win(race))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_winning_charge_times() {
+        assert_eq!(4, count_winning_charge_times(7, 9));
+        assert_eq!(8, count_winning_charge_times(15, 40));
+        assert_eq!(9, count_winning_charge_times(30, 200));
+        assert_eq!(71503, count_winning_charge_times(71530, 940200));
+    }
+
+    #[test]
+    fn test_count_winning_charge_times_without_winners() {
+        assert_eq!(0, count_winning_charge_times(0, 0));
+        assert_eq!(0, count_winning_charge_times(10, 25));
+        assert_eq!(0, count_winning_charge_times(10, 1000));
+    }
+
+    #[test]
+    fn test_count_matches_simulation() {
+        for time in 0..60 {
+            for record in 0..(time * time / 4 + 2) {
+                let race = Race { time, record };
+
+                assert_eq!(race.simulated_ways_to_win(), race.ways_to_win(), "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_kerned() {
+        let races = Day6::default().parse("Time:      7  15   30\nDistance:  9  40  200").unwrap();
+
+        assert_eq!(3, races.races.len());
+        assert_eq!(Ok(Race { time: 71530, record: 940200 }), races.kerned);
+    }
+
+    #[test]
+    fn test_kerning_derives_second_inputs() {
+        for (input, kerned_input) in [
+            (include_str!("bin/day6/day6-sample.txt"), include_str!("bin/day6/day6-sample-2.txt")),
+            (include_str!("bin/day6/day6-jc.txt"), include_str!("bin/day6/day6-jc-2.txt")),
+            (include_str!("bin/day6/day6-dfu.txt"), include_str!("bin/day6/day6-dfu-2.txt"))
+        ] {
+            let races = Day6::default().parse(input).unwrap();
+            let kerned_races = Day6::default().parse(kerned_input).unwrap();
+
+            assert_eq!(kerned_races.races, vec![races.kerned.unwrap()]);
+        }
+    }
+
+    #[test]
+    fn test_parse_kerned_overflow() {
+        let input = "Time: 300000 300000\nDistance: 9999999999 9999999999";
+        let races = Day6::default().parse(input).unwrap();
+
+        // only part 2 reads the kerned numbers
+        assert_eq!(Ok(count_winning_charge_times(300000, 9999999999).pow(2)), Day6::default().part1(&races));
+        assert_eq!(Err(ParseError::invalid_number(11, "99999999999999999999").at_line(2)), Day6::default().part2(&races));
+        assert_eq!(Day6::default().part1(&races), Day6::default().part1_streaming(input.as_bytes()));
+    }
+
+    #[test]
+    fn test_count_large_race() {
+        let half = 1u64 << 31;
+
+        assert_eq!(1, count_winning_charge_times(2 * half, half * half - 1));
+        assert_eq!(2, count_winning_charge_times(2 * half + 1, half * (half + 1) - 1));
+        assert_eq!(0, count_winning_charge_times(2 * half, half * half));
+    }
+
+    #[test]
+    fn test_streaming_matches_parsed() {
+        for input in [
+            include_str!("bin/day6/day6-sample.txt"),
+            include_str!("bin/day6/day6-jc.txt"),
+            include_str!("bin/day6/day6-dfu.txt")
+        ] {
+            for day6 in [Day6::default(), Day6 { kerning: true, ..Default::default() }] {
+                let races = day6.parse(input).unwrap();
+
+                assert_eq!(day6.part1(&races), day6.part1_streaming(input.as_bytes()));
+                assert_eq!(day6.part2(&races), day6.part2_streaming(input.as_bytes()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_product_overflow() {
+        let input = "Time: 4000000000 4000000000 4000000000\nDistance: 1 1 1";
+        let day6 = Day6::default();
+
+        assert_eq!(Err(ParseError::too_large("the product of the ways to win")), day6.part1(&day6.parse(input).unwrap()));
+        assert_eq!(Err(ParseError::too_large("the product of the ways to win").at_line(2).offset(14)), day6.part1_streaming(input.as_bytes()));
+    }
+
+    #[test]
+    fn test_streaming_errors() {
+        let day6 = Day6::default();
+
+        assert_eq!(Err(ParseError::invalid_number(13, "x").at_line(2)), day6.part1_streaming("Time: 7 15\nDistance: 9 x".as_bytes()));
+        assert_eq!(Err(ParseError::missing(12, "2 distances").at_line(2)), day6.part1_streaming("Time: 7 15\nDistance: 9".as_bytes()));
+        assert_eq!(Err(ParseError::unexpected(1, "Speed:", "\"Distance:\" row").at_line(2)), day6.part2_streaming("Time: 7\nSpeed: 9".as_bytes()));
+        assert_eq!(Err(ParseError::invalid_number(19, "99999999999")), day6.part2_streaming("Time: 99999999999 99999999999\nDistance: 1".as_bytes()));
+    }
+}
@@ -0,0 +1,676 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt::{self, Display, Write}, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{error::{end_column, parse_number}, ParseError};
+
+/// A card, ordered by how strong it is in the deck it was dealt from.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Hash)]
+pub struct Card {
+    rank: u8,
+    symbol: char
+}
+
+impl Card {
+    /// How strong the card is, from 0 for the weakest card of its deck.
+    pub fn rank(&self) -> u8 {
+        self.rank
+    }
+
+    pub fn symbol(&self) -> char {
+        self.symbol
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol)
+    }
+}
+
+/// Writes `cards` the way they are written in the input, e.g. `32T3K`.
+pub fn format_cards(cards: &[Card]) -> String {
+    cards.iter().map(Card::to_string).collect()
+}
+
+/// The cards that can be dealt and how many of them make a hand.
+#[derive(clap::Args, Debug, Clone, PartialEq, Eq)]
+pub struct Deck {
+    /// The cards of the deck, from the weakest to the strongest.
+    #[arg(long, default_value = Deck::STANDARD_CARDS, value_parser = parse_deck_cards)]
+    pub cards: String,
+
+    /// How many cards make a hand.
+    #[arg(long, default_value_t = 5, value_parser = parse_hand_size)]
+    pub hand_size: usize
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self { cards: Deck::STANDARD_CARDS.to_string(), hand_size: 5 }
+    }
+}
+
+impl Deck {
+    /// The cards of the puzzle, from the weakest to the strongest.
+    pub const STANDARD_CARDS: &'static str = "23456789TJQKA";
+
+    /// The most cards a deck can have, so that any of them can be wild.
+    pub const MAX_CARDS: usize = 64;
+
+    /// The card written as `symbol`.
+    pub fn card(&self, symbol: char) -> Result<Card, ParseError> {
+        match self.cards.chars().position(|c| c == symbol) {
+            Some(rank) => Ok(Card { rank: rank as u8, symbol }),
+            None => {
+                let expected = format!("a card ({})", self.cards.chars().rev().collect::<String>());
+
+                Err(ParseError::unexpected(1, &symbol.to_string(), expected))
+            }
+        }
+    }
+
+    /// Parses which cards are wild, e.g. `J2`.
+    pub fn wildcards(&self, symbols: &str) -> Result<RuleSet, ParseError> {
+        symbols.chars()
+            .enumerate()
+            .try_fold(RuleSet::STANDARD, |rules, (i, c)| {
+                Ok(rules.with_wildcard(self.card(c).map_err(|e| e.offset(i))?))
+            })
+    }
+}
+
+fn parse_deck_cards(cards: &str) -> Result<String, String> {
+    if cards.is_empty() {
+        return Err(String::from("a deck needs at least one card"));
+    } else if cards.chars().count() > Deck::MAX_CARDS {
+        return Err(format!("a deck can have at most {} cards", Deck::MAX_CARDS));
+    } else if let Some(card) = cards.chars().duplicates().next() {
+        return Err(format!("{:?} is in the deck more than once", card));
+    } else if cards.contains(char::is_whitespace) {
+        return Err(String::from("cards can't be whitespace"));
+    }
+
+    Ok(cards.to_string())
+}
+
+fn parse_hand_size(size: &str) -> Result<usize, String> {
+    match size.parse() {
+        Ok(size @ 1..=255) => Ok(size),
+        _ => Err(String::from("expected a hand size from 1 to 255"))
+    }
+}
+
+/// The kind of a hand: how many there are of each of its cards, from the card
+/// there is the most of to the card there is the least of.
+///
+/// Kinds are ordered by comparing those counts one by one, which for hands of
+/// five cards puts five of a kind (5) above four of a kind (4 1), four of a
+/// kind above a full house (3 2), and so on.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash)]
+pub struct Kind {
+    counts: Vec<u8>
+}
+
+impl Kind {
+    pub fn counts(&self) -> &[u8] {
+        &self.counts
+    }
+
+    /// The name of a kind of hand of five cards.
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.counts[..] {
+            [5] => "FiveOfAKind",
+            [4, 1] => "FourOfAKind",
+            [3, 2] => "FullHouse",
+            [3, 1, 1] => "ThreeOfAKind",
+            [2, 2, 1] => "TwoPair",
+            [2, 1, 1, 1] => "OnePair",
+            [1, 1, 1, 1, 1] => "HighCard",
+            _ => return None
+        })
+    }
+
+    /// Every kind of hand of `hand_size` cards, from the weakest to the
+    /// strongest.
+    pub fn all(hand_size: usize) -> Vec<Kind> {
+        fn partitions(left: usize, largest: usize, counts: &mut Vec<u8>, kinds: &mut Vec<Kind>) {
+            if left == 0 {
+                return kinds.push(Kind { counts: counts.clone() });
+            }
+
+            for count in 1..=left.min(largest) {
+                counts.push(count as u8);
+                partitions(left - count, count, counts, kinds);
+                counts.pop();
+            }
+        }
+
+        let mut kinds = Vec::new();
+        partitions(hand_size, hand_size, &mut Vec::new(), &mut kinds);
+        kinds.sort();
+        kinds
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Kind {
+    fn from(value: [u8; N]) -> Self {
+        Self { counts: value.to_vec() }
+    }
+}
+
+impl Display for Kind {
+    /// Writes the name of the kind, or its counts if it has none, e.g. `3-2-1-1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.pad(name),
+            None => f.pad(&self.counts.iter().join("-"))
+        }
+    }
+}
+
+/// Which cards are wild. Wild cards stand in for whatever card makes the
+/// strongest kind of hand, but are weaker than every other card when breaking
+/// ties between hands of the same kind.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Default, Hash)]
+pub struct RuleSet {
+    wildcards: u64
+}
+
+impl RuleSet {
+    /// No wild cards, as in part 1.
+    pub const STANDARD: RuleSet = RuleSet { wildcards: 0 };
+
+    /// Jacks are jokers, as in part 2. Only meant for the standard deck, where
+    /// jacks are the tenth weakest card.
+    pub const JOKERS_WILD: RuleSet = RuleSet { wildcards: 1 << 9 };
+
+    /// Makes `card` wild as well.
+    pub fn with_wildcard(self, card: Card) -> Self {
+        Self { wildcards: self.wildcards | 1 << card.rank }
+    }
+
+    pub fn is_wild(&self, card: Card) -> bool {
+        self.wildcards & 1 << card.rank != 0
+    }
+
+    /// Compares two cards when breaking ties.
+    pub fn cmp_cards(&self, a: Card, b: Card) -> Ordering {
+        (!self.is_wild(a), a).cmp(&(!self.is_wild(b), b))
+    }
+
+    /// The kind of hand `cards` make, with every wild card standing in for the
+    /// card there is the most of.
+    pub fn kind(&self, cards: &[Card]) -> Kind {
+        let wildcards = cards.iter().filter(|card| self.is_wild(**card)).count() as u8;
+        let mut counts: Vec<_> = cards.iter()
+            .filter(|card| !self.is_wild(**card))
+            .counts()
+            .into_values()
+            .map(|count| count as u8)
+            .collect();
+
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        match counts.first_mut() {
+            Some(count) => *count += wildcards,
+            None => counts.push(wildcards)
+        }
+
+        Kind { counts }
+    }
+
+    /// The cards with every wild card replaced by the card it stands in for:
+    /// the card there is the most of, or the strongest of those if there are
+    /// several. Hands of only wild cards become the strongest of them.
+    pub fn promote(&self, cards: &[Card]) -> Vec<Card> {
+        let best = cards.iter()
+            .filter(|card| !self.is_wild(**card))
+            .counts()
+            .into_iter()
+            .max_by_key(|(card, count)| (*count, **card))
+            .map(|(card, _)| *card)
+            .or_else(|| cards.iter().max().copied());
+
+        cards.iter()
+            .map(|card| match best {
+                Some(best) if self.is_wild(*card) => best,
+                _ => *card
+            })
+            .collect()
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = ParseError;
+
+    // J2, with the standard deck
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        Deck::default().wildcards(value)
+    }
+}
+
+/// A hand of cards and its bid. The kind of the hand is worked out once, when
+/// the hand is made, rather than every time it is compared.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hand {
+    cards: Vec<Card>,
+    pub bid: u64,
+    rules: RuleSet,
+    kind: Kind
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Card>, bid: u64, rules: RuleSet) -> Self {
+        let kind = rules.kind(&cards);
+
+        Self { cards, bid, rules, kind }
+    }
+
+    /// Parses a hand like `32T3K 765`, dealt from `deck` and played by `rules`.
+    pub fn parse_with(value: &str, deck: &Deck, rules: RuleSet) -> Result<Self, ParseError> {
+        let Some((cards_str, bid)) = value.split_once(' ') else {
+            return Err(ParseError::missing(end_column(value), "bid"));
+        };
+
+        let cards = cards_str.chars()
+            .enumerate()
+            .map(|(i, c)| deck.card(c).map_err(|e| e.offset(i)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if cards.len() != deck.hand_size {
+            return Err(ParseError::unexpected(1, cards_str, format!("{} cards", deck.hand_size)));
+        }
+
+        Ok(Self::new(cards, parse_number(value, bid)?, rules))
+    }
+
+    /// The same hand, played by other rules.
+    pub fn with_rules(&self, rules: RuleSet) -> Self {
+        Self::new(self.cards.clone(), self.bid, rules)
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+
+    /// The hand its wild cards were promoted to, if it has any.
+    pub fn promoted(&self) -> Option<Vec<Card>> {
+        self.cards.iter()
+            .any(|card| self.rules.is_wild(*card))
+            .then(|| self.rules.promote(&self.cards))
+    }
+
+    /// Compares how strong two hands are, leaving their bids out of it. Hands
+    /// played by different rules are never equally strong: they're ordered by
+    /// their rules first, so that the order stays total.
+    pub fn cmp_strength(&self, other: &Self) -> Ordering {
+        self.rules.cmp(&other.rules)
+            .then_with(|| self.kind.cmp(&other.kind))
+            .then_with(|| {
+                self.cards.iter().zip(other.cards.iter())
+                    .map(|(a, b)| self.rules.cmp_cards(*a, *b))
+                    .find(|ord| ord.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    // 32T3K 765
+    fn from_str(value: &str) -> Result<Self, ParseError> {
+        Hand::parse_with(value, &Deck::default(), RuleSet::STANDARD)
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders hands by strength, then by bid, so that hands with the same cards
+/// only compare equal when they bid the same too.
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_strength(other).then(self.bid.cmp(&other.bid))
+    }
+}
+
+/// How hands with the same cards are ranked against each other.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TiePolicy {
+    /// The hand with the lower bid ranks lower.
+    #[default]
+    Bid,
+    /// The hand that comes first in the input ranks lower.
+    InputOrder,
+    /// The hands share the lowest of the ranks they would take up, and the
+    /// ranks after them are skipped.
+    Shared
+}
+
+/// Every hand along with its rank, from the weakest hand to the strongest.
+pub fn ranked(hands: &[Hand], ties: TiePolicy) -> Vec<(u64, &Hand)> {
+    let mut sorted: Vec<_> = hands.iter().collect();
+
+    match ties {
+        TiePolicy::Bid => sorted.sort(),
+        // the sort is stable, so tied hands stay in input order
+        TiePolicy::InputOrder | TiePolicy::Shared => sorted.sort_by(|a, b| a.cmp_strength(b))
+    }
+
+    let mut ranks: Vec<(u64, &Hand)> = Vec::with_capacity(sorted.len());
+
+    for (i, hand) in sorted.into_iter().enumerate() {
+        let rank = match ranks.last() {
+            Some((rank, previous)) if ties == TiePolicy::Shared && hand.cmp_strength(previous).is_eq() => *rank,
+            _ => i as u64 + 1
+        };
+
+        ranks.push((rank, hand));
+    }
+
+    ranks
+}
+
+/// A hand's bid multiplied by its rank.
+fn winnings(rank: u64, hand: &Hand) -> Result<u64, ParseError> {
+    rank.checked_mul(hand.bid)
+        .ok_or_else(|| ParseError::too_large(format!("what {} wins", format_cards(&hand.cards))))
+}
+
+/// Adds `winnings` to the total so far.
+fn add_winnings(total: u64, winnings: u64) -> Result<u64, ParseError> {
+    total.checked_add(winnings)
+        .ok_or_else(|| ParseError::too_large("the sum of winnings"))
+}
+
+/// Sums each hand's bid multiplied by its rank, failing if that doesn't fit
+/// in a u64.
+pub fn total_winnings(hands: &[Hand], ties: TiePolicy) -> Result<u64, ParseError> {
+    ranked(hands, ties).into_iter()
+        .try_fold(0, |total, (rank, hand)| add_winnings(total, winnings(rank, hand)?))
+}
+
+/// The largest hands whose kinds are all listed by [`kind_histogram`], 15
+/// kinds for 7 cards. The number of kinds grows quickly after that.
+pub const MAX_LISTED_HAND_SIZE: usize = 7;
+
+/// How many hands there are of each kind. Kinds no hand is of are included
+/// too, as long as there are few enough to list, which stops at hands of
+/// [`MAX_LISTED_HAND_SIZE`] cards.
+pub fn kind_histogram(hands: &[Hand]) -> BTreeMap<Kind, usize> {
+    let hand_size = hands.first().map_or(0, |hand| hand.cards.len());
+    let mut histogram = BTreeMap::new();
+
+    if hand_size <= MAX_LISTED_HAND_SIZE {
+        histogram.extend(Kind::all(hand_size).into_iter().map(|kind| (kind, 0)));
+    }
+
+    for hand in hands {
+        *histogram.entry(hand.kind.clone()).or_default() += 1;
+    }
+
+    histogram
+}
+
+/// Lists every hand from the weakest to the strongest along with its kind,
+/// what its wild cards were promoted to, its rank and what it wins, followed
+/// by how many hands there are of each kind. Fails like [`total_winnings`].
+pub fn explain(hands: &[Hand], ties: TiePolicy) -> Result<String, ParseError> {
+    let histogram = kind_histogram(hands);
+    let cards_width = hands.first().map_or(0, |hand| hand.cards.len()).max(5);
+    let kind_width = histogram.keys().map(|kind| kind.to_string().len()).max().unwrap_or_default().max(12);
+    let mut output = String::new();
+    let mut total = 0;
+
+    writeln!(
+        output, "{:>5}  {:<cards_width$}  {:<kind_width$}  {:<promoted_width$}  {:>5}  {:>9}",
+        "rank", "cards", "kind", "promoted", "bid", "winnings", promoted_width = cards_width.max(8)
+    ).unwrap();
+
+    for (rank, hand) in ranked(hands, ties) {
+        let promoted = hand.promoted().map_or(String::from("-"), |cards| format_cards(&cards));
+        let winnings = winnings(rank, hand)?;
+        total = add_winnings(total, winnings)?;
+
+        writeln!(
+            output, "{:>5}  {:<cards_width$}  {:<kind_width$}  {:<promoted_width$}  {:>5}  {:>9}",
+            rank, format_cards(&hand.cards), hand.kind, promoted, hand.bid, winnings, promoted_width = cards_width.max(8)
+        ).unwrap();
+    }
+
+    writeln!(output, "Total winnings: {}", total).unwrap();
+    writeln!(output).unwrap();
+
+    let most = histogram.values().copied().max().unwrap_or_default().max(1);
+
+    for (kind, count) in histogram {
+        // bars are at most 40 characters long
+        let bar = "#".repeat((count * 40).div_ceil(most));
+
+        writeln!(output, "{}", format!("{:<kind_width$}  {:>5}  {}", kind, count, bar).trim_end()).unwrap();
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn cards(symbols: &str) -> Vec<Card> {
+        symbols.chars().map(|c| Deck::default().card(c).unwrap()).collect()
+    }
+
+    fn card(symbol: char) -> Card {
+        Deck::default().card(symbol).unwrap()
+    }
+
+    fn hand(symbols: &str, rules: RuleSet) -> Hand {
+        Hand::new(cards(symbols), 0, rules)
+    }
+
+    #[test]
+    fn test_hand_parse() {
+        assert_eq!(Ok(Hand::new(cards("32T3K"), 765, RuleSet::STANDARD)), "32T3K 765".parse());
+        assert_eq!(Err(ParseError::unexpected(4, "X", "a card (AKQJT98765432)")), "32TXK 765".parse::<Hand>());
+        assert_eq!(Err(ParseError::unexpected(1, "32T3", "5 cards")), "32T3 765".parse::<Hand>());
+    }
+
+    #[test]
+    fn test_cmp_hand_1st() {
+        for rules in [RuleSet::STANDARD, RuleSet::JOKERS_WILD] {
+            let a = hand("33332", rules);
+            let b = hand("2AAAA", rules);
+
+            assert!(a > b);
+        }
+    }
+
+    #[test]
+    fn test_cmp_hand_2nd() {
+        for rules in [RuleSet::STANDARD, RuleSet::JOKERS_WILD] {
+            let a = hand("77888", rules);
+            let b = hand("77788", rules);
+
+            assert!(a > b);
+        }
+    }
+
+    #[test]
+    fn test_cmp_hand_with_sample_data() {
+        let a = "KTJJT";
+        let b = "KK677";
+
+        assert!(hand(a, RuleSet::STANDARD) < hand(b, RuleSet::STANDARD));
+        // jokers turn KTJJT into four of a kind
+        assert!(hand(a, RuleSet::JOKERS_WILD) > hand(b, RuleSet::JOKERS_WILD));
+    }
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Kind::from([2, 2, 1]), RuleSet::STANDARD.kind(&cards("KTJJT")));
+        assert_eq!(Kind::from([4, 1]), RuleSet::JOKERS_WILD.kind(&cards("KTJJT")));
+        assert_eq!(Kind::from([5]), RuleSet::JOKERS_WILD.kind(&cards("JJJJJ")));
+        assert_eq!(Kind::from([3, 2]), RuleSet::STANDARD.kind(&cards("23232")));
+        assert_eq!(Kind::from([1, 1, 1, 1, 1]), RuleSet::STANDARD.kind(&cards("23456")));
+    }
+
+    #[test]
+    fn test_with_rules_updates_kind() {
+        let hand = hand("KTJJT", RuleSet::STANDARD);
+
+        assert_eq!(&Kind::from([2, 2, 1]), hand.kind());
+        assert_eq!(&Kind::from([4, 1]), hand.with_rules(RuleSet::JOKERS_WILD).kind());
+    }
+
+    #[test]
+    fn test_custom_wildcards() {
+        let twos = RuleSet::STANDARD.with_wildcard(card('2'));
+        let twos_and_jokers = RuleSet::JOKERS_WILD.with_wildcard(card('2'));
+
+        assert_eq!(Kind::from([4, 1]), twos.kind(&cards("22KQK")));
+        assert_eq!(Kind::from([5]), twos_and_jokers.kind(&cards("2JKJK")));
+        assert_eq!(Ordering::Less, twos_and_jokers.cmp_cards(card('2'), card('3')));
+        assert_eq!(Ordering::Less, twos_and_jokers.cmp_cards(card('J'), card('3')));
+        assert_eq!(Ordering::Less, twos_and_jokers.cmp_cards(card('2'), card('J')));
+        assert_eq!(Ok(twos_and_jokers), "2J".parse());
+    }
+
+    #[test]
+    fn test_ties() {
+        let hands: Vec<Hand> = ["KK677 30", "32T3K 1", "KK677 10", "KK677 20"].iter()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let ranks = |ties| ranked(&hands, ties).into_iter().map(|(rank, hand)| (rank, hand.bid)).collect::<Vec<_>>();
+
+        assert_eq!(vec![(1, 1), (2, 10), (3, 20), (4, 30)], ranks(TiePolicy::Bid));
+        assert_eq!(vec![(1, 1), (2, 30), (3, 10), (4, 20)], ranks(TiePolicy::InputOrder));
+        assert_eq!(vec![(1, 1), (2, 30), (2, 10), (2, 20)], ranks(TiePolicy::Shared));
+        assert_eq!(Ok(1 + 2 * 60), total_winnings(&hands, TiePolicy::Shared));
+    }
+
+    /// Hands drawn from only a few cards, bids and rules, so that ties are
+    /// common.
+    fn any_hand() -> impl Strategy<Value = Hand> {
+        let rules = prop::sample::select(vec![RuleSet::STANDARD, RuleSet::JOKERS_WILD, RuleSet::JOKERS_WILD.with_wildcard(card('2'))]);
+
+        (prop::collection::vec(prop::sample::select(cards("23JK")), 5), 0..3u64, rules).prop_map(|(cards, bid, rules)| Hand::new(cards, bid, rules))
+    }
+
+    proptest! {
+        #[test]
+        fn test_ord_is_total(a in any_hand(), b in any_hand()) {
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            prop_assert_eq!(a == b, a.cmp(&b).is_eq());
+            prop_assert_eq!(Some(a.cmp(&b)), a.partial_cmp(&b));
+        }
+
+        #[test]
+        fn test_ord_is_transitive(hands in prop::array::uniform3(any_hand())) {
+            for (a, b, c) in hands.iter().permutations(3).map(|p| (p[0], p[1], p[2])) {
+                if a.cmp(b) == b.cmp(c) {
+                    prop_assert_eq!(a.cmp(b), a.cmp(c));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_promote() {
+        assert_eq!(cards("KTTTT"), RuleSet::JOKERS_WILD.promote(&cards("KTJJT")));
+        // the stronger card wins when there are as many of each
+        assert_eq!(cards("KTKTK"), RuleSet::JOKERS_WILD.promote(&cards("JTKTK")));
+        // hands of only wild cards become the strongest of them
+        assert_eq!(cards("JJJJJ"), RuleSet::JOKERS_WILD.with_wildcard(card('2')).promote(&cards("2JJ2J")));
+        assert_eq!(None, hand("KTJJT", RuleSet::STANDARD).promoted());
+
+        for hand in include_str!("../bin/day7/day7-jc.txt").lines().map(|line| Hand::parse_with(line, &Deck::default(), RuleSet::JOKERS_WILD).unwrap()) {
+            assert_eq!(hand.kind(), &RuleSet::STANDARD.kind(&hand.rules().promote(hand.cards())));
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let hands: Vec<_> = include_str!("../bin/day7/day7-sample.txt").lines()
+            .map(|line| Hand::parse_with(line, &Deck::default(), RuleSet::JOKERS_WILD).unwrap())
+            .collect();
+        let explanation = explain(&hands, TiePolicy::Bid).unwrap();
+        let lines: Vec<_> = explanation.lines().collect();
+
+        assert_eq!("    1  32T3K  OnePair       -           765        765", lines[1]);
+        assert_eq!("    5  KTJJT  FourOfAKind   KTTTT       220       1100", lines[5]);
+        assert_eq!("Total winnings: 5905", lines[6]);
+        assert_eq!("HighCard          0", lines[8]);
+        assert_eq!("FourOfAKind       3  ########################################", lines[13]);
+        assert_eq!(3, kind_histogram(&hands)[&Kind::from([4, 1])]);
+    }
+
+    #[test]
+    fn test_winnings_overflow() {
+        let hands = [hand("32T3K", RuleSet::STANDARD), Hand::new(cards("KK677"), u64::MAX, RuleSet::STANDARD)];
+
+        assert_eq!(Ok(u64::MAX), total_winnings(&hands[1..], TiePolicy::Bid));
+        assert_eq!(Err(ParseError::too_large("what KK677 wins")), total_winnings(&hands, TiePolicy::Bid));
+        assert_eq!(Err(ParseError::too_large("what KK677 wins")), explain(&hands, TiePolicy::Bid));
+        assert_eq!(Err(ParseError::too_large("the sum of winnings")), total_winnings(&[hands[1].clone(), hands[1].clone()], TiePolicy::Shared));
+    }
+
+    #[test]
+    fn test_kind_histogram_of_large_hands() {
+        let hands = [Hand::new(cards(&"2".repeat(80)), 1, RuleSet::STANDARD)];
+
+        assert_eq!(BTreeMap::from([(Kind::from([80]), 1)]), kind_histogram(&hands));
+        assert_eq!(15, kind_histogram(&[Hand::new(cards("2222222"), 1, RuleSet::STANDARD)]).len());
+    }
+
+    #[test]
+    fn test_kinds() {
+        let names: Vec<_> = Kind::all(5).iter().map(Kind::to_string).collect();
+
+        assert_eq!(vec!["HighCard", "OnePair", "TwoPair", "ThreeOfAKind", "FullHouse", "FourOfAKind", "FiveOfAKind"], names);
+        assert_eq!(15, Kind::all(7).len());
+        assert_eq!("3-2-1-1", Kind::from([3, 2, 1, 1]).to_string());
+    }
+
+    #[test]
+    fn test_custom_deck() {
+        let deck = Deck { cards: String::from("xyz"), hand_size: 3 };
+        let jokers = deck.wildcards("x").unwrap();
+        let a = Hand::parse_with("xyz 1", &deck, jokers).unwrap();
+        let b = Hand::parse_with("zzy 2", &deck, jokers).unwrap();
+
+        assert_eq!(&Kind::from([2, 1]), a.kind());
+        assert_eq!(&Kind::from([2, 1]), b.kind());
+        assert!(a < b);
+        assert_eq!(Err(ParseError::unexpected(2, "a", "a card (zyx)")), Hand::parse_with("xaz 1", &deck, jokers));
+        assert_eq!(Err(ParseError::unexpected(1, "xyzz", "3 cards")), Hand::parse_with("xyzz 1", &deck, jokers));
+    }
+
+    #[test]
+    fn test_seven_card_hands() {
+        let deck = Deck { hand_size: 7, ..Deck::default() };
+        let hands: Vec<_> = ["AAKKQQ2 1", "AAAKK23 2", "2222JJJ 3"].iter()
+            .map(|line| Hand::parse_with(line, &deck, RuleSet::JOKERS_WILD).unwrap())
+            .collect();
+
+        assert_eq!(&Kind::from([2, 2, 2, 1]), hands[0].kind());
+        assert_eq!(&Kind::from([3, 2, 1, 1]), hands[1].kind());
+        assert_eq!(&Kind::from([7]), hands[2].kind());
+        assert_eq!(Ok(1 + 2 * 2 + 3 * 3), total_winnings(&hands, TiePolicy::Bid));
+    }
+}
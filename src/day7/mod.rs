@@ -0,0 +1,78 @@
+use crate::{error::parse_lines_with, ParseError, Solution};
+
+pub mod camel_cards;
+
+use camel_cards::{total_winnings, Deck, Hand, RuleSet, TiePolicy};
+
+#[derive(Debug)]
+pub struct Day7 {
+    /// The cards hands are dealt from, in both parts.
+    pub deck: Deck,
+    /// The cards that are wild in part 2, e.g. `J2`. Part 1 always plays by
+    /// the standard rules.
+    pub part2_wild: String,
+    /// How hands with the same cards are ranked, in both parts.
+    pub ties: TiePolicy
+}
+
+impl Default for Day7 {
+    fn default() -> Self {
+        Self { deck: Deck::default(), part2_wild: String::from("J"), ties: TiePolicy::default() }
+    }
+}
+
+impl Day7 {
+    /// The rules of part 2, failing if a wild card isn't in the deck. The
+    /// error is about the configuration rather than the input, so it is at
+    /// line 0.
+    pub fn part2_rules(&self) -> Result<RuleSet, ParseError> {
+        self.deck.wildcards(&self.part2_wild).map_err(|e| e.at_line(0))
+    }
+
+    /// The hands as they are played in part 2.
+    pub fn part2_hands(&self, hands: &[Hand]) -> Result<Vec<Hand>, ParseError> {
+        let rules = self.part2_rules()?;
+
+        Ok(hands.iter().map(|hand| hand.with_rules(rules)).collect())
+    }
+}
+
+impl Solution for Day7 {
+    type Input = Vec<Hand>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Hand>, ParseError> {
+        parse_lines_with(input, |line| Hand::parse_with(line, &self.deck, RuleSet::STANDARD))
+    }
+
+    fn part1(&self, hands: &Vec<Hand>) -> Result<u64, ParseError> {
+        total_winnings(hands, self.ties)
+    }
+
+    fn part2(&self, hands: &Vec<Hand>) -> Result<u64, ParseError> {
+        total_winnings(&self.part2_hands(hands)?, self.ties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part2_wild_follows_deck() {
+        let day7 = Day7 { deck: Deck { cards: String::from("Jxyz"), hand_size: 3 }, ..Day7::default() };
+        let hands = day7.parse("Jxx 2\nyyz 1").unwrap();
+
+        assert_eq!(Ok(4), day7.part1(&hands));
+        // the joker turns Jxx into three of a kind
+        assert_eq!(Ok(5), day7.part2(&hands));
+
+        let day7 = Day7 { deck: Deck { cards: String::from("xyz"), hand_size: 3 }, ..Day7::default() };
+
+        let hands = day7.parse("xyz 1").unwrap();
+
+        assert_eq!(Ok(1), day7.part1(&hands));
+        assert_eq!(Err(ParseError::unexpected(1, "J", "a card (zyx)").at_line(0)), day7.part2(&hands));
+    }
+}
@@ -0,0 +1,223 @@
+use std::{convert::Infallible, error::Error, fmt, str::FromStr};
+
+/// An error found while parsing puzzle input, or while working out an answer
+/// from it.
+///
+/// Lines and columns are 1-based; columns count characters. Parsers for a single
+/// line report line 1, and whoever splits the input into lines moves the
+/// error to the right line with [`ParseError::at_line`]. Errors about the
+/// input as a whole, like a total that overflows, are at line 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Text that should have been a number.
+    InvalidNumber { line: usize, column: usize, text: String },
+    /// Text that isn't any of the things expected in its place.
+    Unexpected { line: usize, column: usize, text: String, expected: String },
+    /// Something required that isn't there at all.
+    Missing { line: usize, column: usize, expected: String },
+    /// A number or a total too large for the integer type it is kept in.
+    TooLarge { line: usize, column: usize, what: String }
+}
+
+impl ParseError {
+    pub fn invalid_number(column: usize, text: &str) -> Self {
+        ParseError::InvalidNumber { line: 1, column, text: text.to_string() }
+    }
+
+    pub fn unexpected(column: usize, text: &str, expected: impl Into<String>) -> Self {
+        ParseError::Unexpected { line: 1, column, text: text.to_string(), expected: expected.into() }
+    }
+
+    pub fn missing(column: usize, expected: impl Into<String>) -> Self {
+        ParseError::Missing { line: 1, column, expected: expected.into() }
+    }
+
+    /// An overflow of `what`, e.g. `"the sum of part numbers"`, that isn't
+    /// tied to a line of the input until moved there with
+    /// [`ParseError::at_line`].
+    pub fn too_large(what: impl Into<String>) -> Self {
+        ParseError::TooLarge { line: 0, column: 1, what: what.into() }
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            ParseError::InvalidNumber { line, .. }
+            | ParseError::Unexpected { line, .. }
+            | ParseError::Missing { line, .. }
+            | ParseError::TooLarge { line, .. } => *line
+        }
+    }
+
+    pub fn column(&self) -> usize {
+        match self {
+            ParseError::InvalidNumber { column, .. }
+            | ParseError::Unexpected { column, .. }
+            | ParseError::Missing { column, .. }
+            | ParseError::TooLarge { column, .. } => *column
+        }
+    }
+
+    /// The offending text, if there is any.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            ParseError::InvalidNumber { text, .. }
+            | ParseError::Unexpected { text, .. } => Some(text),
+            ParseError::Missing { .. } | ParseError::TooLarge { .. } => None
+        }
+    }
+
+    /// Moves the error to the given line of the input.
+    pub fn at_line(mut self, new_line: usize) -> Self {
+        match &mut self {
+            ParseError::InvalidNumber { line, .. }
+            | ParseError::Unexpected { line, .. }
+            | ParseError::Missing { line, .. }
+            | ParseError::TooLarge { line, .. } => *line = new_line
+        }
+
+        self
+    }
+
+    /// Moves the error `columns` further right, for errors found in a part of
+    /// a line that doesn't start at its first column.
+    pub fn offset(mut self, columns: usize) -> Self {
+        match &mut self {
+            ParseError::InvalidNumber { column, .. }
+            | ParseError::Unexpected { column, .. }
+            | ParseError::Missing { column, .. }
+            | ParseError::TooLarge { column, .. } => *column += columns
+        }
+
+        self
+    }
+
+    /// Renders the error together with the line of `source` it points at.
+    pub fn report(&self, source: &str) -> String {
+        self.report_line(self.line().checked_sub(1).and_then(|i| source.lines().nth(i)))
+    }
+
+    /// Like [`ParseError::report`], but given just the line the error points
+    /// at, for input that was read one line at a time.
+    pub fn report_line(&self, line: Option<&str>) -> String {
+        let mut report = format!("error: {}", self);
+        let Some(line) = line else { return report };
+
+        let number = self.line().to_string();
+        let padding = " ".repeat(number.len());
+        let width = self.text().map_or(1, |t| t.chars().count().max(1));
+
+        report += &format!("\n{} |\n{} | {}\n{} | {}{}",
+                           padding, number, line,
+                           padding, " ".repeat(self.column() - 1), "^".repeat(width));
+        report
+    }
+
+    /// Prints [`ParseError::report`] to stderr and exits.
+    pub fn exit(&self, source: &str) -> ! {
+        eprintln!("{}", self.report(source));
+        std::process::exit(1);
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidNumber { text, .. } => write!(f, "invalid number {:?}", text)?,
+            ParseError::Unexpected { text, expected, .. } => write!(f, "expected {}, found {:?}", expected, text)?,
+            ParseError::Missing { expected, .. } => write!(f, "missing {}", expected)?,
+            ParseError::TooLarge { what, .. } => write!(f, "{} is too large", what)?
+        }
+
+        if self.line() == 0 {
+            return Ok(());
+        }
+
+        write!(f, " at line {}, column {}", self.line(), self.column())
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<Infallible> for ParseError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+/// The 1-based column at which `part`, a slice of `line`, starts.
+pub(crate) fn column_of(line: &str, part: &str) -> usize {
+    line[..part.as_ptr() as usize - line.as_ptr() as usize].chars().count() + 1
+}
+
+/// The 1-based column just past the end of `line`, for things missing from
+/// it.
+pub(crate) fn end_column(line: &str) -> usize {
+    line.chars().count() + 1
+}
+
+/// Parses `part`, a slice of `line`, as a number.
+pub(crate) fn parse_number<T: FromStr>(line: &str, part: &str) -> Result<T, ParseError> {
+    part.parse().map_err(|_| ParseError::invalid_number(column_of(line, part), part))
+}
+
+/// Parses every non-empty line of `input`, pointing errors at their line.
+pub(crate) fn parse_lines<T: FromStr<Err = ParseError>>(input: &str) -> Result<Vec<T>, ParseError> {
+    parse_lines_with(input, str::parse)
+}
+
+/// Like [`parse_lines`], with `parse` parsing each line.
+pub(crate) fn parse_lines_with<T>(input: &str, mut parse: impl FnMut(&str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+    input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| parse(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        let line = "Game 12: 3 blue";
+
+        assert_eq!(Ok(12u32), parse_number(line, &line[5..7]));
+        assert_eq!(Err(ParseError::invalid_number(6, "12:")), parse_number::<u32>(line, &line[5..8]));
+    }
+
+    #[test]
+    fn test_report() {
+        let error = ParseError::unexpected(3, "purple", "a colour").offset(8).at_line(2);
+
+        assert_eq!("error: expected a colour, found \"purple\" at line 2, column 11
+  |
+2 | Game 2: 1 purple
+  |           ^^^^^^", error.report("Game 1: 1 red\nGame 2: 1 purple"));
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let line = "Game 1: 3 blå, 2 röd";
+        let colour = &line[line.len() - "röd".len()..];
+        let error = ParseError::unexpected(column_of(line, colour), colour, "a colour");
+
+        assert_eq!(18, column_of(line, colour));
+        assert_eq!(21, end_column(line));
+        assert_eq!("error: expected a colour, found \"röd\" at line 1, column 18
+  |
+1 | Game 1: 3 blå, 2 röd
+  |                  ^^^", error.report(line));
+    }
+
+    #[test]
+    fn test_report_too_large() {
+        let error = ParseError::too_large("the sum of part numbers");
+
+        assert_eq!("error: the sum of part numbers is too large", error.report("1"));
+        assert_eq!("error: the sum of part numbers is too large at line 1, column 1
+  |
+1 | 1
+  | ^", error.at_line(1).report("1"));
+    }
+}
@@ -0,0 +1,276 @@
+use std::{ops::{Add, Index, IndexMut}, str::FromStr};
+
+use crate::ParseError;
+
+/// A position on a [`Grid`]. May point outside of it, so that neighbours of
+/// cells on the edge can be computed without special cases.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position(pub i32, pub i32);
+
+impl Position {
+    /// Offsets to the four orthogonal neighbours of a position.
+    pub const ORTHOGONAL: [Position; 4] = [
+        Position( 0, -1),
+        Position(-1,  0),
+        Position( 1,  0),
+        Position( 0,  1)
+    ];
+
+    /// Offsets to all eight neighbours of a position, diagonals included.
+    pub const ADJACENT: [Position; 8] = [
+        Position(-1, -1),
+        Position(-1,  0),
+        Position(-1,  1),
+        Position( 0, -1),
+        Position( 0,  1),
+        Position( 1, -1),
+        Position( 1,  0),
+        Position( 1,  1)
+    ];
+
+    pub fn x(&self) -> i32 {
+        self.0
+    }
+
+    pub fn y(&self) -> i32 {
+        self.1
+    }
+}
+
+impl Add for Position {
+    type Output = Position;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Position(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl<'a> Add<&'a Position> for &'a Position {
+    type Output = Position;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Position(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells, row by row.
+    ///
+    /// # Panics
+    /// If there aren't exactly `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(width * height, cells.len(), "grid cells don't fill a {}x{} grid", width, height);
+
+        Self { width, height, cells }
+    }
+
+    /// Parses a grid with one cell per character and one row per line.
+    pub fn parse_with<F>(input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(Position, char) -> Result<T, ParseError>
+    {
+        // widths and columns are counted in characters, as there is one cell
+        // per character
+        let Some(width) = input.lines().next().map(|line| line.chars().count()) else {
+            return Err(ParseError::missing(1, "a grid row"));
+        };
+
+        let mut cells = Vec::new();
+
+        for (y, line) in input.lines().enumerate() {
+            let expected = format!("a row of width {}", width);
+            let length = line.chars().count();
+
+            if length < width {
+                return Err(ParseError::missing(length + 1, expected).at_line(y + 1));
+            } else if length > width {
+                let overflow: String = line.chars().skip(width).collect();
+
+                return Err(ParseError::unexpected(width + 1, &overflow, expected).at_line(y + 1));
+            }
+
+            for (x, ch) in line.chars().enumerate() {
+                cells.push(cell(Position(x as i32, y as i32), ch).map_err(|e| e.at_line(y + 1))?);
+            }
+        }
+
+        Ok(Self::new(width, cells.len() / width.max(1), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        let Position(x, y) = position;
+
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn offset(&self, position: Position) -> Option<usize> {
+        self.contains(position)
+            .then(|| position.y() as usize * self.width + position.x() as usize)
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.offset(position).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.offset(position).map(|i| &mut self.cells[i])
+    }
+
+    /// Every position on the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+
+        (0..self.cells.len()).map(move |i| Position((i % width) as i32, (i / width) as i32))
+    }
+
+    /// Every cell on the grid along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[(y * self.width)..((y + 1) * self.width)])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of 0, and an empty grid has no rows
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).filter_map(|x| self.column(x))
+    }
+
+    /// The cells among `offsets` away from `position` that are on the grid.
+    pub fn neighbours<'a>(&'a self, position: Position, offsets: &'a [Position]) -> impl Iterator<Item = (Position, &'a T)> {
+        offsets.iter()
+            .map(move |offset| &position + offset)
+            .filter_map(|p| self.get(p).map(|cell| (p, cell)))
+    }
+
+    /// The up to four cells sharing an edge with `position`.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &Position::ORTHOGONAL)
+    }
+
+    /// The up to eight cells sharing an edge or a corner with `position`.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &Position::ADJACENT)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", position, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        let (width, height) = (self.width, self.height);
+
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", position, width, height))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        Self::parse_with(input, |_, ch| Ok(ch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "abc\ndef";
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!('a', grid[Position(0, 0)]);
+        assert_eq!('f', grid[Position(2, 1)]);
+        assert_eq!(None, grid.get(Position(3, 0)));
+        assert_eq!(None, grid.get(Position(0, -1)));
+    }
+
+    #[test]
+    fn test_parse_ragged() {
+        assert_eq!(Err(ParseError::missing(3, "a row of width 3").at_line(2)), "abc\nde".parse::<Grid<char>>());
+        assert_eq!(Err(ParseError::unexpected(4, "g", "a row of width 3").at_line(2)), "abc\ndefg".parse::<Grid<char>>());
+        assert_eq!(Err(ParseError::missing(1, "a grid row")), "".parse::<Grid<char>>());
+    }
+
+    #[test]
+    fn test_parse_multibyte() {
+        let grid: Grid<char> = "aé\nbc".parse().unwrap();
+
+        assert_eq!(2, grid.width());
+        assert_eq!('é', grid[Position(1, 0)]);
+        assert_eq!(Err(ParseError::missing(3, "a row of width 3").at_line(2)), "abc\ndé".parse::<Grid<char>>());
+        assert_eq!(Err(ParseError::unexpected(3, "éé", "a row of width 2").at_line(2)), "ab\ncdéé".parse::<Grid<char>>());
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+
+        assert_eq!(vec![&['a', 'b', 'c'][..], &['d', 'e', 'f'][..]], grid.rows().collect::<Vec<_>>());
+        assert_eq!(Some(&['d', 'e', 'f'][..]), grid.row(1));
+        assert_eq!(vec!['b', 'e'], grid.column(1).unwrap().copied().collect::<Vec<_>>());
+        assert_eq!(3, grid.columns().count());
+        assert!(grid.column(3).is_none());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+
+        let corner: Vec<_> = grid.neighbours8(Position(0, 0)).map(|(_, c)| *c).collect();
+        assert_eq!(vec!['d', 'b', 'e'], corner);
+
+        let middle: Vec<_> = grid.neighbours4(Position(1, 1)).map(|(_, c)| *c).collect();
+        assert_eq!(vec!['b', 'd', 'f'], middle);
+    }
+
+    #[test]
+    fn test_iter_and_map() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+        let upper = grid.map(|c| c.to_ascii_uppercase());
+
+        assert_eq!(Some((Position(1, 1), &'E')), upper.iter().nth(4));
+    }
+}
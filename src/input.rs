@@ -0,0 +1,267 @@
+use std::{fs::File, io::{self, BufRead, BufReader, Read}, path::{Path, PathBuf}, str::FromStr};
+
+use crate::ParseError;
+
+/// Command line arguments selecting a puzzle input, either by path or by
+/// whose input it is. Without either, the input is read from stdin.
+#[derive(clap::Parser, Debug)]
+pub struct InputArgs {
+    /// Path to the puzzle input, or `-` for stdin.
+    #[arg(conflicts_with_all = ["who", "variant"])]
+    pub file_path: Option<PathBuf>,
+
+    /// Whose input to use, e.g. `jc`, `dfu` or `sample`.
+    #[arg(long)]
+    pub who: Option<String>,
+
+    /// Which variant of the input to use, e.g. `2` for `day6-jc-2.txt`.
+    #[arg(long, requires = "who")]
+    pub variant: Option<u8>
+}
+
+impl InputArgs {
+    /// Finds the input file for `day` these arguments refer to, or `None` if
+    /// the input comes from stdin.
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        if let Some(file_path) = &self.file_path {
+            return (file_path.as_os_str() != "-").then(|| file_path.clone());
+        }
+
+        let who = self.who.as_deref()?;
+
+        let path = resolve(day, who, self.variant).unwrap_or_else(|| {
+            eprintln!("No input found for day {} (who: {}, variant: {:?}) in {}",
+                      day, who, self.variant, day_dir(day).display());
+            std::process::exit(1);
+        });
+
+        Some(path)
+    }
+
+    /// Reads the input for `day` these arguments refer to, exiting if it
+    /// can't be read or isn't UTF-8.
+    pub fn read(&self, day: u8) -> String {
+        let mut string = String::new();
+
+        if let Err(e) = self.open(day).read_to_string(&mut string) {
+            let source = self.path(day).map_or(String::from("stdin"), |path| path.display().to_string());
+            exit_on_read_error(&source, e);
+        }
+
+        string
+    }
+
+    /// Opens the input for `day` these arguments refer to, for reading it bit
+    /// by bit with [`records`]. Stdin can only be read once. Exits if the
+    /// file can't be opened.
+    pub fn open(&self, day: u8) -> Box<dyn BufRead> {
+        match self.path(day) {
+            Some(path) => match File::open(&path) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                Err(e) => {
+                    eprintln!("Failed to open {}: {}", path.display(), e);
+                    std::process::exit(1);
+                }
+            },
+            None => Box::new(io::stdin().lock())
+        }
+    }
+
+    /// Hands the records of the input for `day` to `solve`, exiting with a
+    /// report if one of them can't be parsed.
+    pub fn stream<T, A, F>(&self, day: u8, solve: F) -> A
+    where
+        T: FromStr + 'static,
+        T::Err: Into<ParseError>,
+        F: FnOnce(&mut Records<Box<dyn BufRead>, T>) -> Result<A, ParseError>
+    {
+        self.stream_with(day, |line| line.parse().map_err(Into::into), solve)
+    }
+
+    /// Like [`InputArgs::stream`], with `parse` parsing each record.
+    pub fn stream_with<T, A, P, F>(&self, day: u8, parse: P, solve: F) -> A
+    where
+        P: FnMut(&str) -> Result<T, ParseError> + 'static,
+        F: FnOnce(&mut Records<Box<dyn BufRead>, T>) -> Result<A, ParseError>
+    {
+        let mut records = records_with(self.open(day), parse);
+
+        solve(&mut records).unwrap_or_else(|e| {
+            // totals that overflow do so at the record read last
+            let e = if e.line() == 0 { e.at_line(records.line_number) } else { e };
+
+            eprintln!("{}", e.report_line(Some(records.line())));
+            std::process::exit(1);
+        })
+    }
+}
+
+/// Prints that `source` couldn't be read and exits, for input that breaks
+/// off or isn't UTF-8.
+pub(crate) fn exit_on_read_error(source: &str, error: io::Error) -> ! {
+    eprintln!("Failed to read {}: {}", source, error);
+    std::process::exit(1);
+}
+
+/// Parses a single line into a record.
+type ParseRecord<T> = Box<dyn FnMut(&str) -> Result<T, ParseError>>;
+
+/// Parses the lines of a reader one at a time, skipping empty ones. Only the
+/// line being parsed is kept in memory.
+pub struct Records<R, T> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    parse: ParseRecord<T>
+}
+
+/// Reads the records of `reader`, one per line.
+pub fn records<T, R: BufRead>(reader: R) -> Records<R, T>
+where
+    T: FromStr + 'static,
+    T::Err: Into<ParseError>
+{
+    records_with(reader, |line| line.parse().map_err(Into::into))
+}
+
+/// Like [`records`], with `parse` parsing each line.
+pub fn records_with<T, R: BufRead>(reader: R, parse: impl FnMut(&str) -> Result<T, ParseError> + 'static) -> Records<R, T> {
+    Records { reader, line: String::new(), line_number: 0, parse: Box::new(parse) }
+}
+
+impl<R, T> Records<R, T> {
+    /// The text of the line read last, e.g. for reporting an error in it.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+}
+
+impl<R: BufRead, T> Iterator for Records<R, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+
+            if self.reader.read_line(&mut self.line).unwrap_or_else(|e| exit_on_read_error("the input", e)) == 0 {
+                return None;
+            }
+
+            self.line_number += 1;
+
+            let trimmed = self.line.trim_end_matches(['\n', '\r']).len();
+            self.line.truncate(trimmed);
+
+            if !self.line.is_empty() {
+                return Some((self.parse)(&self.line).map_err(|e| e.at_line(self.line_number)));
+            }
+        }
+    }
+}
+
+/// The directory holding every day's inputs.
+pub fn inputs_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src/bin"))
+}
+
+/// The directory holding the inputs for `day`.
+pub fn day_dir(day: u8) -> PathBuf {
+    inputs_dir().join(format!("day{}", day))
+}
+
+/// The names of every input file of `day`, i.e. every `day<day>-*.txt` in its
+/// directory, sorted.
+pub fn inputs(day: u8) -> Vec<String> {
+    let prefix = format!("day{}-", day);
+    let Ok(entries) = std::fs::read_dir(day_dir(day)) else { return Vec::new() };
+
+    let mut names: Vec<_> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+        .collect();
+
+    names.sort();
+    names
+}
+
+/// Finds the input for `day` belonging to `who`.
+///
+/// Inputs are named `day<day>-<who>.txt`, or `day<day>-<who>-<variant>.txt`
+/// when there is more than one. Without a variant, the first variant is used
+/// if there is no unnumbered file.
+pub fn resolve(day: u8, who: &str, variant: Option<u8>) -> Option<PathBuf> {
+    let names = match variant {
+        Some(variant) => vec![format!("day{}-{}-{}.txt", day, who, variant)],
+        None => vec![
+            format!("day{}-{}.txt", day, who),
+            format!("day{}-{}-1.txt", day, who)
+        ]
+    };
+
+    let dir = day_dir(day);
+
+    names.into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(Some(day_dir(5).join("day5-jc.txt")), resolve(5, "jc", None));
+    }
+
+    #[test]
+    fn test_resolve_variant() {
+        assert_eq!(Some(day_dir(6).join("day6-dfu-2.txt")), resolve(6, "dfu", Some(2)));
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_first_variant() {
+        assert_eq!(Some(day_dir(1).join("day1-sample-1.txt")), resolve(1, "sample", None));
+    }
+
+    #[test]
+    fn test_records() {
+        let lines: Vec<_> = records::<String, _>("a\n\nb\r\nc".as_bytes()).collect();
+
+        assert_eq!(vec![Ok("a".to_owned()), Ok("b".to_owned()), Ok("c".to_owned())], lines);
+    }
+
+    #[test]
+    fn test_records_error_line() {
+        let mut records = records::<crate::day2::Game, _>("Game 1: 1 red\n\nGame x: 1 red\n".as_bytes());
+
+        assert!(records.next().unwrap().is_ok());
+        assert_eq!(Some(Err(ParseError::invalid_number(6, "x").at_line(3))), records.next());
+        assert_eq!("Game x: 1 red", records.line());
+    }
+
+    #[test]
+    fn test_stdin_path() {
+        let args = |argv: &[&str]| <InputArgs as clap::Parser>::parse_from([&["aoc"], argv].concat());
+
+        assert_eq!(None, args(&[]).path(1));
+        assert_eq!(None, args(&["-"]).path(1));
+        assert_eq!(Some(PathBuf::from("input.txt")), args(&["input.txt"]).path(1));
+        assert_eq!(resolve(1, "jc", None), args(&["--who", "jc"]).path(1));
+    }
+
+    #[test]
+    fn test_inputs() {
+        let inputs = inputs(7);
+
+        assert!(inputs.contains(&"day7-sample.txt".to_string()));
+        assert!(!inputs.contains(&"notes.txt".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_missing() {
+        assert_eq!(None, resolve(5, "nobody", None));
+        assert_eq!(None, resolve(5, "jc", Some(2)));
+        assert_eq!(None, resolve(42, "jc", None));
+    }
+}
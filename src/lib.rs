@@ -0,0 +1,32 @@
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod solution;
+pub mod verify;
+
+pub use error::ParseError;
+pub use solution::{Solution, Solver};
+
+/// Every day that has a registered solver.
+pub const DAYS: &[u8] = &[1, 2, 3, 4, 5, 6, 7];
+
+/// Returns the solver registered for `day`, if there is one.
+pub fn solver(day: u8) -> Option<Box<dyn Solver>> {
+    match day {
+        1 => Some(Box::new(day1::Day1)),
+        2 => Some(Box::new(day2::Day2::default())),
+        3 => Some(Box::new(day3::Day3::default())),
+        4 => Some(Box::new(day4::Day4::default())),
+        5 => Some(Box::new(day5::Day5::default())),
+        6 => Some(Box::new(day6::Day6::default())),
+        7 => Some(Box::new(day7::Day7::default())),
+        _ => None
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+use crate::ParseError;
+
+/// A solution to a single day's puzzle.
+///
+/// The input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Answers part 1, failing if the answer doesn't fit in its type.
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1, ParseError>;
+
+    /// Answers part 2, failing if the answer doesn't fit in its type.
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2, ParseError>;
+}
+
+/// Object-safe wrapper around [`Solution`], used to keep solutions for
+/// different days in the same registry.
+pub trait Solver {
+    /// Parses `input` and returns the answer to `part` (1 or 2), or `None` if
+    /// there is no such part.
+    fn run(&self, part: u8, input: &str) -> Result<Option<String>, ParseError> {
+        Ok(self.run_all(&[part], input)?.remove(0))
+    }
+
+    /// Parses `input` once and returns the answer to each of `parts`, or
+    /// `None` for parts that don't exist.
+    fn run_all(&self, parts: &[u8], input: &str) -> Result<Vec<Option<String>>, ParseError>;
+}
+
+impl<S: Solution> Solver for S {
+    fn run_all(&self, parts: &[u8], input: &str) -> Result<Vec<Option<String>>, ParseError> {
+        let input = self.parse(input)?;
+
+        parts.iter()
+            .map(|part| Ok(match part {
+                1 => Some(self.part1(&input)?.to_string()),
+                2 => Some(self.part2(&input)?.to_string()),
+                _ => None
+            }))
+            .collect()
+    }
+}
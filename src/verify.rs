@@ -0,0 +1,159 @@
+use std::{collections::{BTreeMap, BTreeSet}, fmt, panic, path::PathBuf, time::{Duration, Instant}};
+
+use serde::Deserialize;
+
+use crate::{input::{day_dir, inputs}, solver};
+
+/// The known-correct answers for a single input file.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExpectedAnswers {
+    pub part1: Option<toml::Value>,
+    pub part2: Option<toml::Value>
+}
+
+impl ExpectedAnswers {
+    pub fn part(&self, part: u8) -> Option<String> {
+        let value = match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None
+        }?;
+
+        Some(match value {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string()
+        })
+    }
+}
+
+/// Expected answers for a day, keyed by input file name.
+pub type Manifest = BTreeMap<String, ExpectedAnswers>;
+
+/// Path to the answers manifest of `day`.
+pub fn manifest_path(day: u8) -> PathBuf {
+    day_dir(day).join("answers.toml")
+}
+
+/// Reads the answers manifest of `day`. Days without a manifest have no
+/// expected answers.
+pub fn load_manifest(day: u8) -> Manifest {
+    let path = manifest_path(day);
+    let Ok(string) = std::fs::read_to_string(&path) else { return Manifest::new() };
+
+    toml::from_str(&string)
+        .unwrap_or_else(|e| panic!("Invalid manifest {}: {}", path.display(), e))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Mismatch { expected: String, actual: String },
+    Fail(String),
+    /// The input has no expected answers, so the answer couldn't be checked.
+    Unverified(String)
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Pass => "PASS",
+            Status::Mismatch { .. } => "MISMATCH",
+            Status::Fail(_) => "FAIL",
+            Status::Unverified(_) => "UNVERIFIED"
+        })
+    }
+}
+
+/// The result of checking one part of one input against the manifest.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub status: Status,
+    /// How long parsing the input and answering every checked part of it took.
+    pub elapsed: Duration
+}
+
+/// Runs the solver of `day` against every input in its directory. Inputs
+/// listed in its manifest are only run for the parts with expected answers,
+/// and the others are run for both parts but can't be checked.
+pub fn verify_day(day: u8) -> Vec<Outcome> {
+    let Some(solver) = solver(day) else { return Vec::new() };
+    let manifest = load_manifest(day);
+    let names: BTreeSet<_> = manifest.keys().cloned().chain(inputs(day)).collect();
+    let mut outcomes = Vec::new();
+
+    for input in names {
+        let expected: Vec<_> = match manifest.get(&input) {
+            Some(answers) => [1, 2].into_iter()
+                .filter_map(|part| answers.part(part).map(|answer| (part, Some(answer))))
+                .collect(),
+            None => vec![(1, None), (2, None)]
+        };
+        let parts: Vec<_> = expected.iter().map(|(part, _)| *part).collect();
+
+        // both parts share a single parse of the input
+        let start = Instant::now();
+        let results = match std::fs::read_to_string(day_dir(day).join(&input)) {
+            Ok(contents) => panic::catch_unwind(panic::AssertUnwindSafe(|| solver.run_all(&parts, &contents)))
+                .map_err(|e| panic_message(&*e))
+                .and_then(|result| result.map_err(|e| e.to_string())),
+            Err(e) => Err(e.to_string())
+        };
+        let elapsed = start.elapsed();
+
+        for (i, (part, expected)) in expected.into_iter().enumerate() {
+            let status = match &results {
+                Ok(answers) => match (&answers[i], expected) {
+                    (None, _) => Status::Fail(format!("no part {}", part)),
+                    (Some(actual), None) => Status::Unverified(actual.clone()),
+                    (Some(actual), Some(expected)) if *actual == expected => Status::Pass,
+                    (Some(actual), Some(expected)) => Status::Mismatch { expected, actual: actual.clone() }
+                },
+                Err(message) => Status::Fail(message.clone())
+            };
+
+            outcomes.push(Outcome { day, part, input: input.clone(), status, elapsed });
+        }
+    }
+
+    outcomes
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_parse() {
+        let manifest: Manifest = toml::from_str(r#"
+            ["day1-sample-1.txt"]
+            part1 = 142
+
+            ["day1-sample-2.txt"]
+            part2 = "281"
+        "#).unwrap();
+
+        assert_eq!(Some("142".to_string()), manifest["day1-sample-1.txt"].part(1));
+        assert_eq!(None, manifest["day1-sample-1.txt"].part(2));
+        assert_eq!(Some("281".to_string()), manifest["day1-sample-2.txt"].part(2));
+    }
+
+    #[test]
+    fn test_every_day_has_a_manifest() {
+        for &day in crate::DAYS {
+            assert!(!load_manifest(day).is_empty(), "day {} has no answers", day);
+        }
+    }
+}